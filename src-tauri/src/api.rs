@@ -0,0 +1,297 @@
+use serde::{Deserialize, Serialize};
+use axum::{extract::{Path, Query, State}, http::{header, HeaderMap, StatusCode}, response::{IntoResponse, Response}, Json};
+use crate::{hints, locator, load::{strip_mc_prefix, SubjectKind}, planner::{self, LocationKind}, recommend, scoring, search::{self, SearchQuery}, spoilers, stats, structs::parse_mc_date, sync::Payload, teams::{self, Team}, timeline, AppState, SharedState};
+
+/// json with an etag of its own, 304 if the client already has it
+fn etagged<T: Serialize>(headers: &HeaderMap, value: &T) -> Response {
+    let payload = Payload::hashed(serde_json::to_vec(value).unwrap());
+    let etag = headers.get(header::IF_NONE_MATCH).and_then(|value| value.to_str().ok());
+    if etag == Some(payload.etag.as_str()) {
+        return StatusCode::NOT_MODIFIED.into_response();
+    }
+
+    (StatusCode::OK, [
+        (header::CONTENT_TYPE, "application/json"),
+        (header::CACHE_CONTROL, "public, max-age=0, must-revalidate"),
+        (header::ETAG, payload.etag.as_str()),
+    ], payload.bytes).into_response()
+}
+
+/// per-player endpoints 404 for players we've never seen
+fn for_player(app: &AppState, uuid: &str, respond: impl FnOnce() -> Response) -> Response {
+    if !app.data.players.contains_key(uuid) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    respond()
+}
+
+/// advancements the viewer isn't allowed to know about yet, none outside spoiler mode
+fn concealed<'a>(app: &'a AppState, viewer: Option<&'a str>) -> impl Fn(&str) -> bool + 'a {
+    move |advancement| match viewer {
+        Some(uuid) if app.config.spoiler_mode => spoilers::is_concealed(&app.data, advancement, uuid),
+        _ => false,
+    }
+}
+
+fn hide_concealed<T>(app: &AppState, viewer: Option<&str>, items: &mut Vec<T>, advancement: impl Fn(&T) -> &str) {
+    let concealed = concealed(app, viewer);
+    items.retain(|item| !concealed(advancement(item)));
+}
+
+#[derive(Deserialize)]
+pub struct ViewerQuery {
+    player: Option<String>, // selected player, for spoiler mode
+}
+
+// ids with a slash in them need it percent-encoded, "blazeandcave:bacap%2Froot"
+pub async fn advancement(State(state): State<SharedState>, Path(id): Path<String>, Query(query): Query<ViewerQuery>, headers: HeaderMap) -> Response {
+    let app = state.read().await;
+    let id = strip_mc_prefix(&id);
+    let Some(advancement) = app.data.advancements.get(id) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    match query.player.filter(|_| app.config.spoiler_mode) {
+        Some(uuid) => etagged(&headers, &spoilers::view(&app.data, id, advancement, &uuid)),
+        None => etagged(&headers, advancement),
+    }
+}
+
+pub async fn advancement_progress(State(state): State<SharedState>, Path(id): Path<String>, headers: HeaderMap) -> Response {
+    let app = state.read().await;
+    let id = strip_mc_prefix(&id);
+    if !app.data.advancements.contains_key(id) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    match app.data.progress.get(id) {
+        Some(progress) => etagged(&headers, progress),
+        None => etagged(&headers, &serde_json::Map::new()),
+    }
+}
+
+pub async fn category(State(state): State<SharedState>, Path(key): Path<String>, headers: HeaderMap) -> Response {
+    let app = state.read().await;
+    match app.data.categories.get(strip_mc_prefix(&key)) {
+        Some(category) => etagged(&headers, category),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+pub async fn players(State(state): State<SharedState>, headers: HeaderMap) -> Response {
+    let app = state.read().await;
+    etagged(&headers, &app.data.players)
+}
+
+pub async fn player(State(state): State<SharedState>, Path(uuid): Path<String>, headers: HeaderMap) -> Response {
+    let app = state.read().await;
+    match app.data.players.get(&uuid) {
+        Some(player) => etagged(&headers, player),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+pub async fn player_progress(State(state): State<SharedState>, Path(uuid): Path<String>, headers: HeaderMap) -> Response {
+    let app = state.read().await;
+    for_player(&app, &uuid, || etagged(&headers, &app.data.player_progress(&uuid)))
+}
+
+#[derive(Deserialize)]
+pub struct LimitQuery {
+    limit: Option<usize>,
+}
+
+pub async fn next_advancements(State(state): State<SharedState>, Path(uuid): Path<String>, Query(query): Query<LimitQuery>) -> Response {
+    let app = state.read().await;
+    for_player(&app, &uuid, || {
+        // filter before the limit so hidden ones don't eat into it
+        let mut recommendations = recommend::recommend(&app.data, &uuid);
+        hide_concealed(&app, Some(&uuid), &mut recommendations, |recommendation| &recommendation.key);
+        recommendations.truncate(query.limit.unwrap_or(recommend::DEFAULT_LIMIT));
+        Json(recommendations).into_response()
+    })
+}
+
+pub async fn player_stat_progress(State(state): State<SharedState>, Path(uuid): Path<String>) -> Response {
+    let app = state.read().await;
+    for_player(&app, &uuid, || Json(stats::stat_progress(&app.data, &uuid)).into_response())
+}
+
+pub async fn player_objective_progress(State(state): State<SharedState>, Path(uuid): Path<String>) -> Response {
+    let app = state.read().await;
+    for_player(&app, &uuid, || Json(stats::objective_progress(&app.data, &app.config.objective_goals, &uuid)).into_response())
+}
+
+pub async fn scoreboard(State(state): State<SharedState>, headers: HeaderMap) -> Response {
+    let app = state.read().await;
+    etagged(&headers, &app.data.scoreboard)
+}
+
+pub async fn item_hints(State(state): State<SharedState>, Path(uuid): Path<String>) -> Response {
+    let app = state.read().await;
+    for_player(&app, &uuid, || {
+        let mut hints = hints::item_hints(&app.data, &app.config.teams, &uuid);
+        hide_concealed(&app, Some(&uuid), &mut hints, |hint| &hint.advancement);
+        Json(hints).into_response()
+    })
+}
+
+pub async fn nearest_locations(State(state): State<SharedState>, Path(uuid): Path<String>) -> Response {
+    let app = state.read().await;
+    for_player(&app, &uuid, || {
+        let mut locations = locator::nearest_locations(&app.data, &uuid);
+        hide_concealed(&app, Some(&uuid), &mut locations, |location| &location.advancement);
+        Json(locations).into_response()
+    })
+}
+
+pub async fn rescan_locations(State(state): State<SharedState>) -> impl IntoResponse {
+    locator::spawn_scan(state);
+    StatusCode::ACCEPTED
+}
+
+#[derive(Deserialize)]
+pub struct ExpeditionQuery {
+    kind: Option<LocationKind>,
+    id: Option<String>,
+}
+
+pub async fn expedition(State(state): State<SharedState>, Path(uuid): Path<String>, Query(query): Query<ExpeditionQuery>) -> Response {
+    let app = state.read().await;
+    for_player(&app, &uuid, || {
+        let progress = app.data.player_progress(&uuid);
+        let id = query.id.as_deref().map(crate::load::strip_mc_prefix);
+        Json(planner::plan(&app.data, &progress, query.kind, id, concealed(&app, Some(&uuid)))).into_response()
+    })
+}
+
+pub async fn player_timeline(State(state): State<SharedState>, Path(uuid): Path<String>) -> Response {
+    let app = state.read().await;
+    for_player(&app, &uuid, || Json(timeline::timeline(&app.data, &uuid)).into_response())
+}
+
+#[derive(Deserialize)]
+pub struct HistoryQuery {
+    at: Option<String>, // rfc3339 or the game's date format, defaults to now
+}
+
+pub async fn player_history(State(state): State<SharedState>, Path(uuid): Path<String>, Query(query): Query<HistoryQuery>) -> impl IntoResponse {
+    let at = match query.at.as_deref() {
+        Some(date) => match parse_mc_date(date) {
+            Some(at) => at,
+            None => return (StatusCode::BAD_REQUEST, "invalid date").into_response(),
+        },
+        None => chrono::Local::now().fixed_offset(),
+    };
+
+    let app = state.read().await;
+    Json(app.history.progress_as_of(&uuid, at)).into_response()
+}
+
+#[derive(Deserialize)]
+pub struct DailyQuery {
+    player: Option<String>,
+}
+
+pub async fn daily_completions(State(state): State<SharedState>, Query(query): Query<DailyQuery>) -> impl IntoResponse {
+    let app = state.read().await;
+    Json(app.history.daily_completions(query.player.as_deref()))
+}
+
+pub async fn search(State(state): State<SharedState>, Query(query): Query<SearchQuery>) -> impl IntoResponse {
+    let app = state.read().await;
+    if query.done.is_some() && query.player.is_none() {
+        return (StatusCode::BAD_REQUEST, "done needs a player").into_response();
+    }
+
+    // filter before the limit so hidden ones don't eat into it
+    let mut hits = search::search(&app.data, &query);
+    hide_concealed(&app, query.player.as_deref(), &mut hits, |hit| &hit.key);
+    if let Some(limit) = query.limit {
+        hits.truncate(limit);
+    }
+    Json(hits).into_response()
+}
+
+pub async fn subject_usages(State(state): State<SharedState>, Path((kind, id)): Path<(SubjectKind, String)>, Query(query): Query<ViewerQuery>) -> impl IntoResponse {
+    let app = state.read().await;
+    let uuid = query.player.as_deref();
+    let mut usages = search::usages(&app.data, kind, &id, uuid);
+    hide_concealed(&app, uuid, &mut usages, |usage| &usage.advancement);
+    Json(usages)
+}
+
+pub async fn teams(State(state): State<SharedState>) -> impl IntoResponse {
+    let app = state.read().await;
+    Json(app.config.teams.clone())
+}
+
+#[derive(Deserialize)]
+pub struct TeamBody {
+    members: Vec<String>,
+}
+
+pub async fn put_team(State(state): State<SharedState>, Path(name): Path<String>, Json(body): Json<TeamBody>) -> impl IntoResponse {
+    let mut app = state.write().await;
+    let app = &mut *app;
+    if name.trim().is_empty() {
+        return (StatusCode::BAD_REQUEST, "team needs a name").into_response();
+    }
+
+    let team = Team { name, members: body.members };
+    match app.config.teams.iter_mut().find(|existing| existing.name == team.name) {
+        Some(existing) => *existing = team.clone(),
+        None => app.config.teams.push(team.clone()),
+    }
+    if let Err(e) = app.config.save() {
+        eprintln!("[CONFIG] Failed to save teams: {}", e);
+    }
+
+    teams::refresh(app, &team);
+    Json(team).into_response()
+}
+
+pub async fn delete_team(State(state): State<SharedState>, Path(name): Path<String>) -> impl IntoResponse {
+    let mut app = state.write().await;
+    let app = &mut *app;
+    let Some(index) = app.config.teams.iter().position(|team| team.name == name) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let team = app.config.teams.remove(index);
+    if let Err(e) = app.config.save() {
+        eprintln!("[CONFIG] Failed to save teams: {}", e);
+    }
+
+    let id = team.id();
+    teams::remove(&mut app.data, &id);
+    app.progress_sync.remove_player(&id);
+    app.events.publish(crate::events::UpdateEvent::TeamRemoved { uuid: id });
+    StatusCode::NO_CONTENT.into_response()
+}
+
+pub async fn leaderboard(State(state): State<SharedState>) -> impl IntoResponse {
+    let app = state.read().await;
+    Json(scoring::leaderboard(&app.data, &app.config.scoring))
+}
+
+pub async fn world_data_files(State(state): State<SharedState>) -> impl IntoResponse {
+    let app = state.read().await;
+    match crate::load::list_data_files(&app.data.world.layout.main) {
+        Ok(files) => Json(files).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+pub async fn world_data_file(State(state): State<SharedState>, Path(file): Path<String>, headers: HeaderMap) -> Response {
+    let world_path = state.read().await.data.world.layout.main.clone();
+    // read outside the lock, these can be big
+    let result = tokio::task::spawn_blocking(move || crate::load::read_data_file(&world_path, &file)).await;
+
+    match result {
+        Ok(Ok(Some(json))) => etagged(&headers, &json),
+        Ok(Ok(None)) => StatusCode::NOT_FOUND.into_response(),
+        Ok(Err(e)) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
@@ -10,6 +10,8 @@ use std::{path::Path as StdPath};
 
 pub const CACHE_DIR: &str = "../cached";
 pub const CACHE_URL: &str = "cached";
+pub const LOCAL_SKINS_DIR: &str = "../skins"; // <name>.png or <uuid>.png, for offline setups
+const HEAD_SIZE: u32 = 32;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Profile {
@@ -55,44 +57,115 @@ impl Cache {
         Ok(())
     }
 
-    pub async fn get_cached_or_fetch(&self, uuid: &str) -> Option<(String, String)> {
-        if let Some(cached_data) = self.get_player(uuid).await {
+    /// `local_name` is what usercache.json or playerdata knew, mojang only gets asked for online uuids
+    pub async fn get_cached_or_fetch(&self, uuid: &str, local_name: Option<&str>) -> Option<(String, String)> {
+        // a uuid standing in for a name gets replaced once we know the real one
+        let cached = self.get_player(uuid).await.filter(|cached| local_name.is_none() || cached.name != uuid);
+        if let Some(cached_data) = cached {
             println!("[CACHE] Hit for profile {}", uuid);
             return Some((cached_data.name, format!("data:image/png;base64,{}", cached_data.face)));
         }
 
-        println!("[CACHE] Miss for profile {}. Fetching from API.", uuid);
+        println!("[CACHE] Miss for profile {}. Resolving.", uuid);
+        let name = match local_name {
+            Some(name) => name.to_string(),
+            None if is_offline_uuid(uuid) => uuid.to_string(), // nothing local knows them, still give them a face
+            None => match crate::outbound::fetch_username(uuid).await {
+                Ok(name) => name,
+                Err(e) => {
+                    eprintln!("[CACHE] Failed to fetch player info for {}: {}", uuid, e);
+                    return None;
+                }
+            },
+        };
 
-        match tokio::try_join!(
-            crate::outbound::fetch_username(uuid),
-            crate::outbound::fetch_user_face(uuid)
-        ) {
-            Ok((name, avatar_bytes)) => {
-                if let Err(e) = self.cache_player(uuid, &name, avatar_bytes.clone()).await {
+        // rate limits and the like shouldn't cost the player their name, they get a default face until next time
+        let avatar_bytes = match player_face(uuid, &name).await {
+            Ok(face) => {
+                if let Err(e) = self.cache_player(uuid, &name, face.clone()).await {
                     eprintln!("[CACHE] Failed to cache player {}: {}", uuid, e);
                 }
-
-                let avatar_base64 = base64::engine::general_purpose::STANDARD.encode(&avatar_bytes);
-                Some((name, format!("data:image/png;base64,{}", avatar_base64)))
+                face
             }
             Err(e) => {
-                eprintln!("[CACHE] Failed to fetch player info for {}: {}", uuid, e);
-                None
+                eprintln!("[CACHE] Failed to get skin for {}, using the default: {}", uuid, e);
+                crate::skins::default_face(uuid).ok()?
             }
+        };
+
+        let avatar_base64 = base64::engine::general_purpose::STANDARD.encode(&avatar_bytes);
+        Some((name, format!("data:image/png;base64,{}", avatar_base64)))
+    }
+}
+
+async fn player_face(uuid: &str, name: &str) -> Result<Vec<u8>> {
+    match player_skin(uuid, name).await? {
+        Some(skin) => crate::skins::render_face(&crate::skins::Skin::decode(&skin)?),
+        None => crate::skins::default_face(uuid),
+    }
+}
+
+/// a local skin file if there is one, otherwise whatever mojang has. none means a default skin
+async fn player_skin(uuid: &str, name: &str) -> Result<Option<Vec<u8>>> {
+    for file_name in [name, uuid] {
+        let local_path = PathBuf::from(LOCAL_SKINS_DIR).join(format!("{}.png", file_name));
+        if local_path.exists() {
+            return Ok(Some(fs::read(local_path).await?));
         }
     }
+
+    if is_offline_uuid(uuid) {
+        return Ok(None);
+    }
+    match crate::outbound::fetch_skin_texture_id(uuid).await? {
+        Some(texture_id) => Ok(Some(cached_skin(&texture_id).await?)),
+        None => Ok(None),
+    }
 }
 
-pub fn cache_world_icon(world_path: &StdPath) -> Result<Option<String>> {
-    let source_icon_path = world_path.join("icon.png");
-    
+/// skins never change under the same texture id, so they're kept around for good
+async fn cached_skin(texture_id: &str) -> Result<Vec<u8>> {
+    let skin_path = PathBuf::from(CACHE_DIR).join("skins").join(format!("{}.png", texture_id));
+    if skin_path.exists() {
+        return Ok(fs::read(skin_path).await?);
+    }
+
+    let skin = crate::outbound::fetch_skin(texture_id).await?;
+    fs::create_dir_all(PathBuf::from(CACHE_DIR).join("skins")).await?;
+    fs::write(skin_path, &skin).await?;
+    Ok(skin)
+}
+
+/// iso head for a player head icon, served from /cached/heads/<texture_id>.png
+pub async fn cache_player_head(texture_id: &str) -> Result<()> {
+    let head_dir = PathBuf::from(CACHE_DIR).join("heads");
+    let head_path = head_dir.join(format!("{}.png", texture_id));
+    if head_path.exists() {
+        return Ok(());
+    }
+
+    let skin = crate::skins::Skin::decode(&cached_skin(texture_id).await?)?;
+    let head = crate::skins::render_head(&skin, HEAD_SIZE)?;
+    fs::create_dir_all(&head_dir).await?;
+    fs::write(&head_path, head).await?;
+    println!("[CACHE] Rendered head {}", texture_id);
+    Ok(())
+}
+
+/// offline mode and lan servers hand out v3 (name based) uuids, mojang has never heard of them
+pub fn is_offline_uuid(uuid: &str) -> bool {
+    uuid::Uuid::parse_str(uuid).is_ok_and(|uuid| uuid.get_version_num() == 3)
+}
+
+/// icon.png in singleplayer, server-icon.png on servers
+pub fn cache_world_icon(source_icon_path: &StdPath) -> Result<Option<String>> {
     if source_icon_path.exists() {
         let dest_dir = PathBuf::from(CACHE_DIR).join("world");
         let dest_icon_path = dest_dir.join("icon.png");
 
         std_fs::create_dir_all(&dest_dir)?;
 
-        std_fs::copy(&source_icon_path, &dest_icon_path)?;
+        std_fs::copy(source_icon_path, &dest_icon_path)?;
         println!("[CACHE] Copied world icon to {:?}", dest_icon_path);
 
         return Ok(Some(format!("/{}/world/icon.png", CACHE_URL)));
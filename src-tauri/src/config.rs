@@ -0,0 +1,34 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use crate::{load::{LayoutMode, ObjectiveGoal}, scoring::ScoringConfig, teams::Team};
+use std::{fs, path::Path};
+
+pub const CONFIG_PATH: &str = "../config.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Config {
+    // hide names, descriptions and requirements of hidden advancements until earned
+    pub spoiler_mode: bool,
+    pub world_layout: LayoutMode, // singleplayer, or a dedicated/paper server folder
+    pub teams: Vec<Team>,
+    pub scoring: ScoringConfig,
+    pub objective_goals: Vec<ObjectiveGoal>,
+}
+
+impl Config {
+    pub fn load() -> Result<Self> {
+        if !Path::new(CONFIG_PATH).exists() {
+            return Ok(Config::default());
+        }
+
+        let content = fs::read_to_string(CONFIG_PATH)
+            .with_context(|| format!("Failed to read config at {}", CONFIG_PATH))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid config in {}", CONFIG_PATH))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(CONFIG_PATH, content).with_context(|| format!("Failed to write config to {}", CONFIG_PATH))
+    }
+}
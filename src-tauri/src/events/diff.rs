@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+use crate::{events::UpdateEvent, history::{ChangeKind, HistoryRecord}, structs::*};
+
+/// turn a player's progress diff into events the frontend can react to one by one
+pub fn progress_events(data: &Data, changes: &[HistoryRecord]) -> Vec<UpdateEvent> {
+    changes.iter()
+        .filter_map(|change| {
+            let uuid = change.uuid.clone();
+            let advancement = change.advancement.clone();
+            match (&change.criterion, change.change) {
+                (Some(criterion), ChangeKind::Gained) => Some(UpdateEvent::CriterionGained {
+                    uuid, advancement,
+                    criterion: criterion.clone(),
+                    at: change.at,
+                }),
+                (Some(criterion), ChangeKind::Lost) => Some(UpdateEvent::CriterionRevoked {
+                    uuid, advancement,
+                    criterion: criterion.clone(),
+                }),
+                (None, ChangeKind::Gained) => {
+                    let definition = data.advancements.get(&advancement)?;
+                    Some(UpdateEvent::AdvancementCompleted {
+                        uuid,
+                        display_name: definition.display_name.clone(),
+                        advancement_type: definition.advancement_type.clone(),
+                        completed_at: change.at,
+                        advancement,
+                    })
+                }
+                (None, ChangeKind::Lost) => None, // the criterion revokes cover it
+            }
+        })
+        .collect()
+}
+
+/// stat category -> stat -> change, only what moved
+pub fn stat_deltas(old: &PlayerStats, new: &PlayerStats) -> HashMap<String, HashMap<String, i64>> {
+    let mut deltas: HashMap<String, HashMap<String, i64>> = HashMap::new();
+    let empty = HashMap::new();
+
+    let categories = old.stats.keys().chain(new.stats.keys());
+    for category in categories {
+        if deltas.contains_key(category) { continue }
+        let before = old.stats.get(category).unwrap_or(&empty);
+        let after = new.stats.get(category).unwrap_or(&empty);
+
+        let changed: HashMap<String, i64> = before.keys().chain(after.keys())
+            .filter_map(|stat| {
+                let delta = after.get(stat).copied().unwrap_or(0) - before.get(stat).copied().unwrap_or(0);
+                (delta != 0).then(|| (stat.clone(), delta))
+            })
+            .collect();
+
+        if !changed.is_empty() {
+            deltas.insert(category.clone(), changed);
+        }
+    }
+    deltas
+}
@@ -0,0 +1,60 @@
+use std::collections::VecDeque;
+use tokio::sync::broadcast;
+use crate::events::UpdateEvent;
+
+const REPLAY_CAPACITY: usize = 256;
+// one player update can publish a lot at once, a lagging subscriber catches up from the replay buffer
+const CHANNEL_CAPACITY: usize = REPLAY_CAPACITY;
+
+#[derive(Clone)]
+pub struct SequencedEvent {
+    pub id: u64,
+    pub event: UpdateEvent,
+}
+
+/// numbered events plus a bounded buffer so reconnecting clients can catch up
+pub struct EventLog {
+    next_id: u64,
+    replay: VecDeque<SequencedEvent>,
+    tx: broadcast::Sender<SequencedEvent>,
+}
+
+impl EventLog {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(CHANNEL_CAPACITY);
+        EventLog { next_id: 1, replay: VecDeque::with_capacity(REPLAY_CAPACITY), tx }
+    }
+
+    pub fn publish(&mut self, event: UpdateEvent) {
+        let sequenced = SequencedEvent { id: self.next_id, event };
+        self.next_id += 1;
+
+        if self.replay.len() == REPLAY_CAPACITY {
+            self.replay.pop_front();
+        }
+        self.replay.push_back(sequenced.clone());
+        self.tx.send(sequenced).ok(); // no subscribers is fine
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<SequencedEvent> {
+        self.tx.subscribe()
+    }
+
+    /// id of the newest event, 0 before anything was published
+    pub fn last_id(&self) -> u64 {
+        self.next_id - 1
+    }
+
+    /// everything after last_id, or None if the buffer doesn't reach back that far
+    pub fn since(&self, last_id: u64) -> Option<Vec<SequencedEvent>> {
+        // ids from another run of the server
+        if last_id >= self.next_id {
+            return None;
+        }
+        let oldest = self.replay.front().map_or(self.next_id, |event| event.id);
+        if last_id + 1 < oldest {
+            return None;
+        }
+        Some(self.replay.iter().filter(|event| event.id > last_id).cloned().collect())
+    }
+}
@@ -1,9 +1,13 @@
 use serde::Serialize;
 use std::collections::HashMap;
-use crate::structs::AdvancementProgress;
+use crate::{load::PlayerState, scoring::PlayerScore, stats::StatProgress, structs::{AdvancementProgress, AdvancementType, Timestamp}};
 
 mod update;
 pub use update::*;
+mod diff;
+pub use diff::*;
+mod log;
+pub use log::*;
 
 use crate::structs::Player;
 
@@ -15,6 +19,60 @@ pub enum UpdateEvent {
         player: Player, // stats
         updated_progress: HashMap<String, AdvancementProgress>
     },
+    #[serde(rename_all = "camelCase")]
+    CriterionGained {
+        uuid: String,
+        advancement: String,
+        criterion: String,
+        at: Option<Timestamp>,
+    },
+    #[serde(rename_all = "camelCase")]
+    CriterionRevoked {
+        uuid: String,
+        advancement: String,
+        criterion: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    AdvancementCompleted {
+        uuid: String,
+        advancement: String,
+        display_name: String,
+        advancement_type: AdvancementType,
+        completed_at: Option<Timestamp>,
+    },
+    #[serde(rename_all = "camelCase")]
+    StatsChanged {
+        uuid: String,
+        deltas: HashMap<String, HashMap<String, i64>>, // category, stat, change
+    },
+    #[serde(rename_all = "camelCase")]
+    StatProgressChanged {
+        uuid: String,
+        progress: Vec<StatProgress>,
+    },
+    #[serde(rename_all = "camelCase")]
+    ScoreboardChanged {
+        changed: HashMap<String, HashMap<String, i32>>, // holder, objective, new score
+        #[serde(skip_serializing_if = "HashMap::is_empty")]
+        removed: HashMap<String, Vec<String>>, // holder, objectives that no longer have a score
+    },
+    #[serde(rename_all = "camelCase")]
+    PlayerStateChanged {
+        uuid: String,
+        state: Option<PlayerState>,
+    },
+    #[serde(rename_all = "camelCase")]
+    WorldDataChanged {
+        file: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    ScoresChanged {
+        players: Vec<PlayerScore>, // full ranking
+    },
+    #[serde(rename_all = "camelCase")]
+    TeamRemoved {
+        uuid: String,
+    },
     // ProfileUpdate {
     //     uuid: String,
     //     name: String,
@@ -1,38 +1,49 @@
 use anyhow::Result;
 use tokio::sync::mpsc;
 use notify::{RecursiveMode, Watcher};
-use crate::{events::UpdateEvent, load::{self}, structs::Player, SharedState};
+use crate::{events::UpdateEvent, load::{self, WorldLayout}, structs::Player, SharedState};
 use crate::structs::AdvancementProgress;
-use std::{collections::HashMap, path::{Path, PathBuf}, time::Duration};
+use std::{collections::HashMap, future::Future, path::{Path, PathBuf}, time::Duration};
 
-const WORLD_PATH: &str = "C:\\Users\\Sebastian\\AppData\\Roaming\\ModrinthApp\\profiles\\My BAC pack\\saves\\New World";
-
-pub fn file_watcher(state: SharedState) -> Result<()> {
+pub fn file_watcher(state: SharedState, layout: WorldLayout) -> Result<()> {
     let (tx, mut rx) = mpsc::channel::<PathBuf>(100);
+    let data_dir = layout.main.join("data");
+    let watched = [layout.advancements.clone(), data_dir.clone(), layout.playerdata.clone()];
 
+    let main_world = layout.main.clone();
+    let playerdata_dir = layout.playerdata.clone();
     tokio::spawn(async move {
         while let Some(path) = rx.recv().await {
+            let Some(file_name) = path.file_name().and_then(|s| s.to_str()).map(str::to_string) else { continue };
+            let directory = path.parent().unwrap_or(Path::new(""));
+
+            if directory == data_dir {
+                if file_name == load::SCOREBOARD_FILE {
+                    spawn_once(&state, file_name.clone(), handle_scoreboard_update(state.clone(), main_world.clone())).await;
+                }
+                if load::is_data_file(&file_name) {
+                    spawn_once(&state, format!("data/{}", file_name), handle_data_file_update(state.clone(), file_name)).await;
+                }
+                continue;
+            }
+
+            if directory == playerdata_dir {
+                // the game also leaves <uuid>.dat_old and temp files around
+                let Some(uuid) = file_name.strip_suffix(".dat").filter(|uuid| uuid::Uuid::parse_str(uuid).is_ok()) else { continue };
+                let uuid = uuid.to_string();
+                spawn_once(&state, format!("playerdata/{}", uuid), {
+                    let state = state.clone();
+                    async move { handle_playerdata_update(state, &path, &uuid).await }
+                }).await;
+                continue;
+            }
+
             if let Some(uuid_str) = path.file_stem().and_then(|s| s.to_str()) {
                 let uuid = uuid_str.to_string();
-
-                // debounce
-                let mut app_write = state.write().await;
-                if !app_write.processing_uuids.insert(uuid.clone()) { continue }
-
-                let uuid_clone = uuid.clone();
-                let update_state = state.clone();
-                let path_clone = path.clone();
-                tokio::spawn({
-                    let update_state = update_state.clone();
-                    async move {
-                        if let Err(e) = handle_player_update(update_state.clone(), &path_clone, &uuid).await {
-                            eprintln!("[ERROR] Failed to process update for {:?}: {}", path_clone, e);
-                        }
-
-                        let mut app_write = update_state.write().await;
-                        app_write.processing_uuids.remove(&uuid_clone);
-                    }
-                });
+                spawn_once(&state, uuid.clone(), {
+                    let state = state.clone();
+                    async move { handle_player_update(state, &path, &uuid).await }
+                }).await;
             }
         }
     });
@@ -48,41 +59,106 @@ pub fn file_watcher(state: SharedState) -> Result<()> {
             }
         }).expect("Failed to create file watcher");
 
-        let advancements_path = Path::new(WORLD_PATH).join("advancements");
-        watcher.watch(&advancements_path, RecursiveMode::NonRecursive).expect("Failed to start watching advancements directory");
-        
+        let [advancements_path, others @ ..] = &watched;
+        watcher.watch(advancements_path, RecursiveMode::NonRecursive).expect("Failed to start watching advancements directory");
         println!("[WATCHER] Now watching for changes in: {:?}", advancements_path);
+
+        // scoreboard, saved data and player positions, not there until the world has been saved once
+        for path in others {
+            match watcher.watch(path, RecursiveMode::NonRecursive) {
+                Ok(()) => println!("[WATCHER] Now watching for changes in: {:?}", path),
+                Err(e) => eprintln!("[WATCHER] Not watching {:?}: {}", path, e),
+            }
+        }
         loop { std::thread::sleep(Duration::from_secs(60)); }
     });
 
     Ok(())
 }
 
+/// one update per key at a time, anything arriving meanwhile gets picked up by the running one
+async fn spawn_once(state: &SharedState, key: String, update: impl Future<Output = Result<()>> + Send + 'static) {
+    if !state.write().await.processing.insert(key.clone()) { return }
+
+    let state = state.clone();
+    tokio::spawn(async move {
+        if let Err(e) = update.await {
+            eprintln!("[ERROR] Failed to process update for {}: {}", key, e);
+        }
+        state.write().await.processing.remove(&key);
+    });
+}
+
+pub async fn handle_scoreboard_update(state: SharedState, world_path: PathBuf) -> Result<()> {
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    let scoreboard = load::read_scoreboard(&world_path)?;
+
+    let mut app = state.write().await;
+    let deltas = load::score_deltas(&app.data.scoreboard, &scoreboard);
+    app.data.scoreboard = scoreboard;
+    if deltas.is_empty() {
+        return Ok(());
+    }
+
+    app.events.publish(UpdateEvent::ScoreboardChanged { changed: deltas.changed, removed: deltas.removed });
+    println!("[UPDATE] Processed scoreboard update");
+    Ok(())
+}
+
+/// nothing to re-read, clients fetch /api/world/data/{file} if they're looking at it
+pub async fn handle_data_file_update(state: SharedState, file: String) -> Result<()> {
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    state.write().await.events.publish(UpdateEvent::WorldDataChanged { file });
+    Ok(())
+}
+
+pub async fn handle_playerdata_update(state: SharedState, path: &Path, uuid: &str) -> Result<()> {
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    let player_state = load::read_player_state(path)?;
+
+    let mut app = state.write().await;
+    let app = &mut *app;
+    // players show up with their first advancements/stats save
+    let Some(player) = app.data.players.get_mut(uuid) else { return Ok(()) };
+    if player.state == player_state {
+        return Ok(());
+    }
+    player.state = player_state.clone();
+
+    app.events.publish(UpdateEvent::PlayerStateChanged { uuid: uuid.to_string(), state: player_state });
+    app.progress_sync.update_player(&app.data, uuid);
+    Ok(())
+}
+
 pub async fn handle_player_update(state: SharedState, path: &Path, uuid: &str) -> Result<()> {
     tokio::time::sleep(Duration::from_millis(150)).await;
 
-    let stats_path = path.parent().unwrap().parent().unwrap()
-        .join("stats").join(format!("{}.json", uuid));
+    let stats_path = state.read().await.data.world.layout.stats.join(format!("{}.json", uuid));
     let new_stats = load::read_player_stats(&stats_path)?;
 
     let mut app = state.write().await;
 
-    let player = if let Some(player) = app.data.players.get_mut(uuid) {
-        player.stats = new_stats;
-        player.clone()
-    } else {
-        Player {
-            uuid: uuid.to_string(),
-            stats: new_stats.clone(),
-            ..Default::default()
-        }
-    };
+    let previous_stats = app.data.players.get(uuid).map(|player| player.stats.clone()).unwrap_or_default();
+    let stat_deltas = super::stat_deltas(&previous_stats, &new_stats);
+
+    let player = app.data.players.entry(uuid.to_string()).or_insert_with(|| Player {
+        uuid: uuid.to_string(),
+        ..Default::default()
+    });
+    player.stats = new_stats;
+    let player = player.clone();
 
+    let previous_progress = app.data.player_progress(uuid);
+    let previous_score = crate::scoring::player_score(&app.data, &app.config.scoring, uuid);
     for old_progress in app.data.progress.values_mut() {
         old_progress.remove(uuid);
     }
 
-    let progress: HashMap<String, AdvancementProgress> = load::read_player_advancement_progress(path)?;
+    let mut progress: HashMap<String, AdvancementProgress> = load::read_player_advancement_progress(path)?;
+    for (advancement_key, progress_details) in progress.iter_mut() {
+        progress_details.completed_at = load::completion_time(app.data.advancements.get(advancement_key), progress_details);
+    }
+
     for (advancement_key, progress_details) in &progress {
         app.data.progress
             .entry(advancement_key.clone())
@@ -90,15 +166,39 @@ pub async fn handle_player_update(state: SharedState, path: &Path, uuid: &str) -
             .insert(uuid.to_string(), progress_details.clone());
     }
     
-    app.update_tx.send(UpdateEvent::ProgressUpdate {
+    let changes = crate::history::diff_progress(uuid, &previous_progress, &progress, chrono::Local::now().fixed_offset());
+    for event in super::progress_events(&app.data, &changes) {
+        app.events.publish(event);
+    }
+    if !stat_deltas.is_empty() {
+        let stat_progress = crate::stats::changed_stat_progress(&app.data, uuid, &stat_deltas);
+        if !stat_progress.is_empty() {
+            app.events.publish(UpdateEvent::StatProgressChanged { uuid: uuid.to_string(), progress: stat_progress });
+        }
+        app.events.publish(UpdateEvent::StatsChanged { uuid: uuid.to_string(), deltas: stat_deltas });
+    }
+
+    if let Err(e) = app.history.record(changes) {
+        eprintln!("[HISTORY] Failed to record changes for {}: {}", uuid, e);
+    }
+
+    app.events.publish(UpdateEvent::ProgressUpdate {
         uuid: uuid.to_string(),
         player,
         updated_progress: progress,
-    }).ok();
+    });
+
+    let app = &mut *app;
+    app.progress_sync.update_player(&app.data, uuid);
 
-    let (etag, data_bytes) = crate::build_response_bytes(&app.data);
-    app.etag = etag;
-    app.data_bytes = data_bytes;
+    if crate::scoring::player_score(&app.data, &app.config.scoring, uuid) != previous_score {
+        let players = crate::scoring::leaderboard(&app.data, &app.config.scoring).players;
+        app.events.publish(UpdateEvent::ScoresChanged { players });
+    }
+
+    for team in crate::teams::teams_with(app, uuid) {
+        crate::teams::refresh(app, &team);
+    }
     
     println!("[UPDATE] Processed full update for player {}", uuid);
     Ok(())
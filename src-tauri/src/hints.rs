@@ -0,0 +1,140 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use crate::{load::{strip_mc_prefix, BaseSubject}, structs::*, teams::Team};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ItemLocation {
+    Inventory,
+    EnderChest,
+}
+
+/// where a player is keeping an item, container is the shulker box or bundle it's inside
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Holding {
+    pub uuid: String,
+    pub location: ItemLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container: Option<String>,
+    pub count: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemHint {
+    pub advancement: String,
+    pub criterion: String,
+    pub item: String,
+    pub own: bool, // false means a teammate has it
+    pub holdings: Vec<Holding>,
+}
+
+/// unmet item criteria that the player, or someone on their team, can already do
+pub fn item_hints(data: &Data, teams: &[Team], uuid: &str) -> Vec<ItemHint> {
+    let (own_holders, teammates) = holders(teams, uuid);
+    let holdings = holdings_by_item(data, &[own_holders.clone(), teammates].concat());
+
+    let mut keys: Vec<&String> = data.advancements.keys().collect();
+    keys.sort();
+
+    let mut hints = Vec::new();
+    for key in keys {
+        let advancement = &data.advancements[key];
+        let progress = data.progress.get(key).and_then(|players| players.get(uuid));
+        if advancement.command_driven || data.is_done(key, uuid) {
+            continue;
+        }
+
+        for criterion in advancement.remaining_criteria(progress) {
+            for subject in advancement.requirements.get(&criterion).into_iter().flatten() {
+                let BaseSubject::Item { ids, .. } = &subject.base else { continue };
+                // ids are already expanded from tags, so any of them will do
+                for id in ids {
+                    let Some(item_holdings) = holdings.get(id.as_str()) else { continue };
+                    if hints.iter().any(|hint: &ItemHint| hint.advancement == *key && hint.criterion == criterion && hint.item == *id) {
+                        continue;
+                    }
+                    let own: Vec<Holding> = item_holdings.iter().filter(|h| own_holders.contains(&h.uuid)).cloned().collect();
+                    hints.push(ItemHint {
+                        advancement: key.clone(),
+                        criterion: criterion.clone(),
+                        item: id.clone(),
+                        own: !own.is_empty(),
+                        holdings: if own.is_empty() { item_holdings.clone() } else { own },
+                    });
+                }
+            }
+        }
+    }
+    hints
+}
+
+/// whose items count as the player's own (all members for a team), and whose are a teammate's
+fn holders(teams: &[Team], uuid: &str) -> (Vec<String>, Vec<String>) {
+    let mut own = vec![uuid.to_string()];
+    let mut teammates: Vec<String> = Vec::new();
+    for team in teams {
+        if team.id() == uuid {
+            own.extend(team.members.iter().cloned());
+        } else if team.members.iter().any(|member| member == uuid) {
+            teammates.extend(team.members.iter().filter(|member| *member != uuid).cloned());
+        }
+    }
+    teammates.retain(|member| !own.contains(member));
+    teammates.sort();
+    teammates.dedup();
+    (own, teammates)
+}
+
+fn holdings_by_item(data: &Data, holders: &[String]) -> HashMap<String, Vec<Holding>> {
+    let mut holdings: HashMap<String, Vec<Holding>> = HashMap::new();
+    for uuid in holders {
+        let Some(state) = data.players.get(uuid).and_then(|player| player.state.as_ref()) else { continue };
+        for (location, items) in [(ItemLocation::Inventory, &state.inventory), (ItemLocation::EnderChest, &state.ender_chest)] {
+            for item in items {
+                collect_item(&mut holdings, uuid, location, None, &item.id, item.count, item.components.as_ref());
+            }
+        }
+    }
+    holdings
+}
+
+fn collect_item(
+    holdings: &mut HashMap<String, Vec<Holding>>,
+    uuid: &str,
+    location: ItemLocation,
+    container: Option<&str>,
+    id: &str,
+    count: i32,
+    components: Option<&serde_json::Value>
+) {
+    let entries = holdings.entry(id.to_string()).or_default();
+    match entries.iter_mut().find(|h| h.uuid == uuid && h.location == location && h.container.as_deref() == container) {
+        Some(existing) => existing.count += count,
+        None => entries.push(Holding { uuid: uuid.to_string(), location, container: container.map(str::to_string), count }),
+    }
+
+    for (nested_id, nested_count, nested_components) in contents(components) {
+        collect_item(holdings, uuid, location, Some(container.unwrap_or(id)), &nested_id, nested_count, nested_components);
+    }
+}
+
+/*
+    shulker boxes: "minecraft:container": [{ slot: 0, item: { id: "minecraft:diamond", count: 3 } }]
+    bundles: "minecraft:bundle_contents": [{ id: "minecraft:diamond", count: 3 }]
+*/
+fn contents(components: Option<&serde_json::Value>) -> Vec<(String, i32, Option<&serde_json::Value>)> {
+    let Some(components) = components else { return Vec::new() };
+    let container = components.get("minecraft:container").and_then(|v| v.as_array()).into_iter().flatten()
+        .filter_map(|entry| entry.get("item"));
+    let bundle = components.get("minecraft:bundle_contents").and_then(|v| v.as_array()).into_iter().flatten();
+
+    container.chain(bundle)
+        .filter_map(|item| {
+            let id = strip_mc_prefix(item.get("id")?.as_str()?).to_string();
+            let count = item.get("count").and_then(|v| v.as_i64()).unwrap_or(1) as i32;
+            Some((id, count, item.get("components")))
+        })
+        .collect()
+}
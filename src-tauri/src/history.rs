@@ -0,0 +1,240 @@
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::{collections::{BTreeMap, BTreeSet, HashMap}, fs, io::Write, path::PathBuf};
+use crate::{cache::CACHE_DIR, structs::*};
+
+pub const HISTORY_FILE: &str = "history.jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    Gained,
+    Lost,
+}
+
+/// one line in the log. no criterion means the advancement itself was completed or lost
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryRecord {
+    pub observed_at: Timestamp,
+    pub uuid: String,
+    pub advancement: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub criterion: Option<String>,
+    pub change: ChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub at: Option<Timestamp>, // date from the save file, for gains
+}
+
+impl HistoryRecord {
+    /// the date to show: when the game says a gain happened, when we noticed a loss
+    fn effective_time(&self) -> Timestamp {
+        match self.change {
+            ChangeKind::Gained => self.at.unwrap_or(self.observed_at),
+            ChangeKind::Lost => self.observed_at,
+        }
+    }
+}
+
+/// append-only log of every criterion gain and loss we've seen, survives rollbacks
+pub struct History {
+    path: PathBuf,
+    records: Vec<HistoryRecord>,
+}
+
+impl History {
+    pub fn open() -> Result<Self> {
+        let path = PathBuf::from(CACHE_DIR).join(HISTORY_FILE);
+        let mut records = Vec::new();
+
+        if path.exists() {
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read history at {}", path.display()))?;
+            for line in content.lines().filter(|line| !line.trim().is_empty()) {
+                match serde_json::from_str(line) {
+                    Ok(record) => records.push(record),
+                    Err(e) => eprintln!("[HISTORY] Skipping bad line: {}", e),
+                }
+            }
+        }
+
+        println!("[HISTORY] Loaded {} records", records.len());
+        Ok(History { path, records })
+    }
+
+    pub fn record(&mut self, records: Vec<HistoryRecord>) -> Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(CACHE_DIR)?;
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(&self.path)
+            .with_context(|| format!("Failed to open history at {}", self.path.display()))?;
+
+        let mut buffer = String::new();
+        for record in &records {
+            buffer.push_str(&serde_json::to_string(record)?);
+            buffer.push('\n');
+        }
+        file.write_all(buffer.as_bytes())?;
+
+        println!("[HISTORY] Recorded {} changes", records.len());
+        self.records.extend(records);
+        Ok(())
+    }
+
+    /// catch up on whatever changed while we weren't running
+    pub fn record_snapshot(&mut self, data: &Data) -> Result<()> {
+        let now = Local::now().fixed_offset();
+        let mut changes = Vec::new();
+        let mut uuids: Vec<&String> = data.players.keys().filter(|uuid| !crate::teams::is_team(uuid)).collect();
+        uuids.sort();
+
+        for uuid in uuids {
+            let known = self.progress_as_of(uuid, now);
+            changes.extend(diff_progress(uuid, &known, &data.player_progress(uuid), now));
+        }
+        self.record(changes)
+    }
+
+    /// replay everything we had seen by a point in time, in the order we saw it.
+    /// a criterion restored from a backup keeps its old date but only counts from the restore
+    pub fn progress_as_of(&self, uuid: &str, at: Timestamp) -> HashMap<String, AdvancementProgress> {
+        let mut relevant: Vec<&HistoryRecord> = self.records.iter()
+            .filter(|record| record.uuid == uuid && record.observed_at <= at)
+            .collect();
+        relevant.sort_by_key(|record| record.observed_at);
+
+        let mut progress: HashMap<String, AdvancementProgress> = HashMap::new();
+        for record in relevant {
+            let entry = progress.entry(record.advancement.clone()).or_default();
+            match (&record.criterion, record.change) {
+                (Some(criterion), ChangeKind::Gained) => {
+                    entry.requirement_progress.insert(criterion.clone(), record.effective_time());
+                }
+                (Some(criterion), ChangeKind::Lost) => {
+                    entry.requirement_progress.remove(criterion);
+                }
+                (None, ChangeKind::Gained) => {
+                    entry.done = true;
+                    entry.completed_at = Some(record.effective_time());
+                }
+                (None, ChangeKind::Lost) => {
+                    entry.done = false;
+                    entry.completed_at = None;
+                }
+            }
+        }
+
+        progress.retain(|_, entry| entry.done || !entry.requirement_progress.is_empty());
+        progress
+    }
+
+    /// advancement completions per day, net of losses
+    pub fn daily_completions(&self, uuid: Option<&str>) -> BTreeMap<NaiveDate, i64> {
+        let mut days = BTreeMap::new();
+        for record in &self.records {
+            if record.criterion.is_some() || uuid.is_some_and(|uuid| uuid != record.uuid) {
+                continue;
+            }
+            let day = record.effective_time().date_naive();
+            *days.entry(day).or_insert(0) += match record.change {
+                ChangeKind::Gained => 1,
+                ChangeKind::Lost => -1,
+            };
+        }
+        days
+    }
+}
+
+/// every criterion and completion that differs between two progress snapshots of one player
+pub fn diff_progress(
+    uuid: &str,
+    old: &HashMap<String, AdvancementProgress>,
+    new: &HashMap<String, AdvancementProgress>,
+    observed_at: Timestamp
+) -> Vec<HistoryRecord> {
+    let empty = AdvancementProgress::default();
+    let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    let mut records = Vec::new();
+
+    let mut push = |advancement: &str, criterion: Option<&String>, change, at| records.push(HistoryRecord {
+        observed_at,
+        uuid: uuid.to_string(),
+        advancement: advancement.to_string(),
+        criterion: criterion.cloned(),
+        change, at,
+    });
+
+    for key in keys {
+        let before = old.get(key).unwrap_or(&empty);
+        let after = new.get(key).unwrap_or(&empty);
+
+        let gained: BTreeSet<&String> = after.requirement_progress.keys()
+            .filter(|criterion| !before.requirement_progress.contains_key(*criterion))
+            .collect();
+        let lost: BTreeSet<&String> = before.requirement_progress.keys()
+            .filter(|criterion| !after.requirement_progress.contains_key(*criterion))
+            .collect();
+
+        for criterion in gained {
+            push(key.as_str(), Some(criterion), ChangeKind::Gained, after.requirement_progress.get(criterion).copied());
+        }
+        for criterion in lost {
+            push(key.as_str(), Some(criterion), ChangeKind::Lost, None);
+        }
+
+        match (before.done, after.done) {
+            (false, true) => push(key.as_str(), None, ChangeKind::Gained, after.completed_at),
+            (true, false) => push(key.as_str(), None, ChangeKind::Lost, None),
+            _ => {}
+        }
+    }
+
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(date: &str) -> Timestamp {
+        parse_mc_date(date).unwrap()
+    }
+
+    fn criterion(observed_at: &str, change: ChangeKind, at: Option<&str>) -> HistoryRecord {
+        HistoryRecord {
+            observed_at: time(observed_at),
+            uuid: "player".to_string(),
+            advancement: "minecraft:story/mine_stone".to_string(),
+            criterion: Some("get_stone".to_string()),
+            change,
+            at: at.map(time),
+        }
+    }
+
+    fn held_at(history: &History, at: &str) -> Option<Timestamp> {
+        history.progress_as_of("player", time(at))
+            .get("minecraft:story/mine_stone")
+            .and_then(|progress| progress.requirement_progress.get("get_stone").copied())
+    }
+
+    #[test]
+    fn restored_criterion_only_counts_from_the_restore() {
+        let gained = "2025-06-01 12:00:00 +0000";
+        let history = History {
+            path: PathBuf::new(),
+            records: vec![
+                criterion("2025-06-01 12:05:00 +0000", ChangeKind::Gained, Some(gained)),
+                criterion("2025-06-02 12:00:00 +0000", ChangeKind::Lost, None), // rolled back
+                criterion("2025-06-03 12:00:00 +0000", ChangeKind::Gained, Some(gained)), // backup restored
+            ],
+        };
+
+        assert_eq!(held_at(&history, "2025-06-01 12:01:00 +0000"), None);
+        assert_eq!(held_at(&history, "2025-06-01 18:00:00 +0000"), Some(time(gained)));
+        assert_eq!(held_at(&history, "2025-06-02 18:00:00 +0000"), None);
+        assert_eq!(held_at(&history, "2025-06-03 18:00:00 +0000"), Some(time(gained)));
+    }
+}
@@ -1,10 +1,9 @@
 use anyhow::{Context, Result};
-use base64::Engine;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
 use std::collections::BTreeMap;
-use std::{collections::HashMap, path::Path};
+use std::{collections::HashMap, path::{Path, PathBuf}};
 use crate::structs::*;
 use crate::load::*;
 
@@ -19,14 +18,61 @@ pub struct Advancement {
     pub parent: Option<String>,
     pub category: String,
     pub requirements: BTreeMap<String, Vec<Subject>>,
+    pub requirement_groups: Vec<Vec<String>>, // and of ors, like the json
     #[serde(skip_serializing_if = "Option::is_none")]
     pub common_subjects: Option<Vec<Subject>>,
     pub display_name: String,
     pub description: String,
+    #[serde(skip_serializing_if = "is_false", default)]
+    pub hidden: bool,
+    pub show_toast: bool,
+    pub announce_to_chat: bool,
+    #[serde(skip)]
+    pub background: Option<String>, // only roots have one, goes on the category
     pub spreadsheet_info: SpreadsheetInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rewards: Option<Rewards>,
+    // only impossible/tick triggers, the requirements don't mean anything
+    #[serde(skip_serializing_if = "is_false", default)]
+    pub command_driven: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub granted_by: Vec<FunctionReference>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub revoked_by: Vec<FunctionReference>,
+    // score checks on stat objectives in the granting functions
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stat_goals: Vec<StatGoal>,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Rewards {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experience: Option<i32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub loot: Vec<LootReward>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recipes: Vec<RecipeReward>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>, // bac trophies and announcements
 }
 
-pub fn load_all_advancements(jar_path: &Path, world_path: &Path, enabled_datapacks: &Vec<String>) -> Result<HashMap<String, Advancement>> {
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LootReward {
+    pub table: String,
+    pub items: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeReward {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item: Option<String>,
+}
+
+pub fn load_all_advancements(jar_path: &Path, datapack_dirs: &[PathBuf], enabled_datapacks: &Vec<String>) -> Result<(HashMap<String, Advancement>, Worldgen, SubjectIndex)> {
 
     requirements::init_debug();
 
@@ -35,7 +81,10 @@ pub fn load_all_advancements(jar_path: &Path, world_path: &Path, enabled_datapac
     ];
 
     for datapack_name in enabled_datapacks {
-        let datapack_path = world_path.join("datapacks").join(datapack_name);
+        let datapack_path = datapack_dirs.iter()
+            .map(|dir| dir.join(datapack_name))
+            .find(|path| path.exists())
+            .unwrap_or_else(|| datapack_dirs[0].join(datapack_name));
         if datapack_path.exists() {
             match open_archive(&datapack_path, datapack_name.clone()) {
                 Ok(archive) => {
@@ -52,6 +101,8 @@ pub fn load_all_advancements(jar_path: &Path, world_path: &Path, enabled_datapac
     }
 
     let context = LoadingContext::from_archives(&mut archives)?;
+    let function_index = FunctionIndex::from_archives(&mut archives)?;
+    let worldgen = Worldgen::from_archives(&mut archives, &context)?;
 
     let categories = vec![FileCategory::Advancement];
     let mut advancements = HashMap::new();
@@ -76,10 +127,52 @@ pub fn load_all_advancements(jar_path: &Path, world_path: &Path, enabled_datapac
         }
     }
     
+    function_index.link(&mut advancements);
+    let subjects = SubjectIndex::build(&advancements, &context);
+
     println!("[LOAD] Successfully loaded {} total advancements", advancements.len());
 
     requirements::write_debug_files();
-    Ok(advancements)
+    Ok((advancements, worldgen, subjects))
+}
+
+impl Advancement {
+    /// requirement groups where none of the criteria are done yet, all of them without progress
+    pub fn unmet_groups(&self, progress: Option<&AdvancementProgress>) -> Vec<&Vec<String>> {
+        self.requirement_groups.iter()
+            .filter(|group| !group.iter().any(|criterion| progress.is_some_and(|progress| progress.has(criterion))))
+            .collect()
+    }
+
+    pub fn remaining_criteria(&self, progress: Option<&AdvancementProgress>) -> Vec<String> {
+        let mut remaining: Vec<String> = Vec::new();
+        for group in self.unmet_groups(progress) {
+            for criterion in group {
+                if !remaining.contains(criterion) {
+                    remaining.push(criterion.clone());
+                }
+            }
+        }
+        remaining
+    }
+}
+
+/// latest of the earliest criterion per requirement group, or just the latest criterion
+pub fn completion_time(advancement: Option<&Advancement>, progress: &AdvancementProgress) -> Option<Timestamp> {
+    if !progress.done {
+        return None;
+    }
+
+    let from_groups = advancement
+        .filter(|advancement| !advancement.requirement_groups.is_empty())
+        .and_then(|advancement| {
+            advancement.requirement_groups.iter()
+                .map(|group| group.iter().filter_map(|criterion| progress.requirement_progress.get(criterion)).min())
+                .collect::<Option<Vec<&Timestamp>>>()
+        })
+        .and_then(|group_times| group_times.into_iter().max().copied());
+
+    from_groups.or_else(|| progress.requirement_progress.values().max().copied())
 }
 
 fn load_advancement(
@@ -99,6 +192,17 @@ struct AdvancementJson {
     parent: Option<String>,
     criteria: HashMap<String, Criteria>,
     requirements: Option<Vec<Vec<String>>>,
+    rewards: Option<RewardsJson>,
+}
+
+#[derive(Deserialize)]
+struct RewardsJson {
+    experience: Option<i32>,
+    #[serde(default)]
+    loot: Vec<String>,
+    #[serde(default)]
+    recipes: Vec<String>,
+    function: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -107,6 +211,17 @@ pub struct DisplayJson {
     pub description: Value,
     pub icon: Value,
     pub frame: Option<String>,
+    pub background: Option<String>,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default = "default_true")]
+    pub show_toast: bool,
+    #[serde(default = "default_true")]
+    pub announce_to_chat: bool,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 fn json_to_advancement(context: &LoadingContext, json: &AdvancementJson, id: &str) -> Result<Advancement> {
@@ -148,6 +263,22 @@ fn json_to_advancement(context: &LoadingContext, json: &AdvancementJson, id: &st
         }
     }
 
+    // no requirements means every criterion is needed
+    let requirement_groups = match &json.requirements {
+        Some(groups) => groups.clone(),
+        None => {
+            let mut criteria: Vec<&String> = json.criteria.keys().collect();
+            criteria.sort();
+            criteria.into_iter().map(|criterion| vec![criterion.clone()]).collect()
+        }
+    };
+    let requirement_groups = requirement_groups.into_iter()
+        .map(|group| group.iter().map(|criterion| strip_mc_prefix(criterion).to_string()).collect())
+        .collect();
+
+    let command_driven = !json.criteria.is_empty()
+        && json.criteria.values().all(|criterion| is_command_trigger(&criterion.trigger));
+
     let source = if id.contains(":") {
         id.split(":").next().unwrap().to_string()
     } else {
@@ -159,14 +290,50 @@ fn json_to_advancement(context: &LoadingContext, json: &AdvancementJson, id: &st
         display_name: translate(&display.title, &context),
         description: translate(&display.description, &context),
         icon: json_to_icon(&display.icon),
-        source,parent, advancement_type, requirements, common_subjects,
+        hidden: display.hidden,
+        show_toast: display.show_toast,
+        announce_to_chat: display.announce_to_chat,
+        background: display.background.as_deref().map(|bg| strip_mc_prefix(bg).to_string()),
+        source,parent, advancement_type, requirements, common_subjects, requirement_groups,
+        rewards: json.rewards.as_ref().and_then(|rewards| json_to_rewards(rewards, context)),
+        command_driven,
 
         // later
         category: String::new(),
-        spreadsheet_info: SpreadsheetInfo { class: "".to_string(), requirement_details: None },
+        spreadsheet_info: SpreadsheetInfo { class: "".to_string(), requirement_details: None, time: None },
+        granted_by: Vec::new(),
+        revoked_by: Vec::new(),
+        stat_goals: Vec::new(),
     })
 }
 
+fn json_to_rewards(json: &RewardsJson, context: &LoadingContext) -> Option<Rewards> {
+    let loot = json.loot.iter()
+        .map(|table| LootReward {
+            table: strip_mc_prefix(table).to_string(),
+            items: context.loot_table_items(table),
+        })
+        .collect();
+
+    let recipes = json.recipes.iter()
+        .map(|recipe| RecipeReward {
+            id: strip_mc_prefix(recipe).to_string(),
+            item: context.recipe_result(recipe).cloned(),
+        })
+        .collect();
+
+    let rewards = Rewards {
+        experience: json.experience.filter(|xp| *xp > 0),
+        function: json.function.as_deref().map(|f| strip_mc_prefix(f).to_string()),
+        loot, recipes,
+    };
+
+    if rewards.experience.is_none() && rewards.loot.is_empty() && rewards.recipes.is_empty() && rewards.function.is_none() {
+        return None;
+    }
+    Some(rewards)
+}
+
 fn json_to_icon(json: &serde_json::Value) -> Icon {
     if let Some(id) = json.get("id").and_then(|id| id.as_str()) {
         // get the player head skin component if its a head
@@ -200,16 +367,7 @@ fn get_player_head(json: &serde_json::Value) -> Result<Icon> {
     for property in properties {
         if property.get("name").and_then(|n| n.as_str()) == Some("textures") {
             let b64 = property.get("value").and_then(|v| v.as_str()).context("Missing texture value")?;
-            let decoded = base64::prelude::BASE64_STANDARD.decode(b64)?;
-            let texture_data: serde_json::Value = serde_json::from_slice(&decoded)?;
-
-            let skin_url = texture_data.get("textures")
-                .and_then(|t| t.get("SKIN"))
-                .and_then(|s| s.get("url"))
-                .and_then(|u| u.as_str())
-                .context("Could not find SKIN url in texture data")?;
-            
-            let texture_id = skin_url.split("/").last().context("Invalid skin URL format")?.to_string();
+            let texture_id = crate::skins::skin_texture_id(b64)?.context("Could not find SKIN url in texture data")?;
             return Ok(Icon::PlayerHead { texture_id });
         }
     }
@@ -10,6 +10,10 @@ pub enum FileCategory {
     Advancement,
     Tags,
     Recipe,
+    LootTable,
+    Function,
+    Biome,
+    Structure,
     Language,
     // Textures
 }
@@ -28,6 +32,18 @@ impl FileCategory {
             FileCategory::Recipe => {
                 path.contains("/recipe/") && path.ends_with(".json")
             }
+            FileCategory::LootTable => {
+                path.contains("/loot_table/") && path.ends_with(".json")
+            }
+            FileCategory::Function => {
+                path.contains("/function/") && path.ends_with(".mcfunction")
+            }
+            FileCategory::Biome => {
+                path.contains("/worldgen/biome/") && path.ends_with(".json") && !path.contains("/tags/")
+            }
+            FileCategory::Structure => {
+                path.contains("/worldgen/structure/") && path.ends_with(".json") && !path.contains("/tags/")
+            }
             FileCategory::Language => {
                 path.ends_with("/lang/en_us.json")
             }
@@ -114,7 +130,8 @@ impl Archive for DirArchive {
         
         for category in categories {
             match category {
-                FileCategory::Advancement | FileCategory::Tags | FileCategory::Recipe => {
+                FileCategory::Advancement | FileCategory::Tags | FileCategory::Recipe | FileCategory::LootTable | FileCategory::Function |
+                FileCategory::Biome | FileCategory::Structure => {
                     dirs.insert("data".to_string());
                 }
                 FileCategory::Language => {
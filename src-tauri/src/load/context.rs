@@ -31,6 +31,7 @@ pub struct LoadingContext {
     pub lang_map: HashMap<String, LangEntry>,
     pub tag_map: HashMap<String, TagData>,
     pub recipe_map: HashMap<String, String>,
+    pub loot_table_map: HashMap<String, LootTableData>,
 }
 
 impl LoadingContext {
@@ -40,11 +41,13 @@ impl LoadingContext {
         let mut lang_map = HashMap::new();
         let mut tag_map = HashMap::new();
         let mut recipe_map = HashMap::new();
+        let mut loot_table_map = HashMap::new();
         
         let categories = vec![
             FileCategory::Language,
             FileCategory::Tags,
             FileCategory::Recipe,
+            FileCategory::LootTable,
         ];
         
         // load in order
@@ -60,21 +63,45 @@ impl LoadingContext {
                     load_tag_from_content(&mut tag_map, &file_path, &content)?;
                 } else if FileCategory::Recipe.matches(&file_path) {
                     load_recipe_file(&mut recipe_map, &file_path, &content)?;
+                } else if FileCategory::LootTable.matches(&file_path) {
+                    load_loot_table_file(&mut loot_table_map, &file_path, &content)?;
                 }
             }
         }
         
-        println!("Loaded {} lang entries, {} recipes, {} tags", lang_map.len(), recipe_map.len(), tag_map.len());
+        println!("Loaded {} lang entries, {} recipes, {} tags, {} loot tables", lang_map.len(), recipe_map.len(), tag_map.len(), loot_table_map.len());
         Ok(LoadingContext {
             lang_map,
             tag_map,
             recipe_map,
+            loot_table_map,
         })
     }
     
     pub fn translate(&self, key: &str) -> Option<String> {
         self.lang_map.get(key).map(|entry| entry.display_name.clone())
     }
+
+    /// recipe ids in rewards may leave out the namespace
+    pub fn recipe_result(&self, recipe_id: &str) -> Option<&String> {
+        self.recipe_map.get(&namespaced(recipe_id))
+    }
+
+    /// every item a loot table can drop, following nested tables and tags
+    pub fn loot_table_items(&self, table_id: &str) -> Vec<String> {
+        let mut items = loot_table_lookup(self, &namespaced(table_id), &mut HashSet::new());
+        let mut seen = HashSet::new();
+        items.retain(|item| seen.insert(item.clone()));
+        items
+    }
+}
+
+fn namespaced(id: &str) -> String {
+    if id.contains(':') {
+        id.to_string()
+    } else {
+        format!("minecraft:{}", id)
+    }
 }
 
 
@@ -300,4 +327,109 @@ fn extract_tag_name(file_path: &str) -> Result<String> {
     } else {
         Err(anyhow::anyhow!("Invalid tag file path format: {}", file_path))
     }
-}
\ No newline at end of file
+}
+
+
+// LOOT TABLES
+#[derive(Debug, Default)]
+pub struct LootTableData {
+    pub items: Vec<String>,
+    pub tags: Vec<String>,
+    pub tables: Vec<String>, // nested loot_table entries
+}
+
+fn load_loot_table_file(
+    loot_table_map: &mut HashMap<String, LootTableData>,
+    file_path: &str,
+    content: &str
+) -> Result<()> {
+    if let Ok(loot_json) = serde_json::from_str::<serde_json::Value>(content) {
+        let mut data = LootTableData::default();
+        if let Some(pools) = loot_json.get("pools").and_then(|v| v.as_array()) {
+            for pool in pools {
+                if let Some(entries) = pool.get("entries").and_then(|v| v.as_array()) {
+                    collect_loot_entries(entries, &mut data);
+                }
+            }
+        }
+        // Later archives override earlier ones (datapack behavior)
+        loot_table_map.insert(extract_loot_table_id(file_path), data);
+    }
+    Ok(())
+}
+
+fn collect_loot_entries(entries: &[serde_json::Value], data: &mut LootTableData) {
+    for entry in entries {
+        let entry_type = entry.get("type").and_then(|v| v.as_str()).map(strip_mc_prefix);
+        match entry_type {
+            Some("item") => {
+                if let Some(name) = entry.get("name").and_then(|v| v.as_str()) {
+                    data.items.push(strip_mc_prefix(name).to_string());
+                }
+            }
+            Some("tag") => {
+                if let Some(name) = entry.get("name").and_then(|v| v.as_str()) {
+                    data.tags.push(name.to_string());
+                }
+            }
+            Some("loot_table") => {
+                // either a reference or an inline table
+                match entry.get("value").or_else(|| entry.get("name")) {
+                    Some(serde_json::Value::String(table_id)) => data.tables.push(namespaced(table_id)),
+                    Some(inline) => {
+                        if let Some(pools) = inline.get("pools").and_then(|v| v.as_array()) {
+                            for pool in pools {
+                                if let Some(entries) = pool.get("entries").and_then(|v| v.as_array()) {
+                                    collect_loot_entries(entries, data);
+                                }
+                            }
+                        }
+                    }
+                    None => {}
+                }
+            }
+            // alternatives, group, sequence
+            _ => {
+                if let Some(children) = entry.get("children").and_then(|v| v.as_array()) {
+                    collect_loot_entries(children, data);
+                }
+            }
+        }
+    }
+}
+
+/// get loot table id from path
+fn extract_loot_table_id(file_path: &str) -> String {
+    // "data/minecraft/loot_table/chests/end_city_treasure.json" -> "minecraft:chests/end_city_treasure"
+    let parts: Vec<&str> = file_path.split('/').collect();
+    if parts.len() >= 4 && parts[0] == "data" && parts[2] == "loot_table" {
+        let namespace = parts[1];
+        let table_path = parts[3..].join("/");
+        format!("{}:{}", namespace, table_path.strip_suffix(".json").unwrap_or(&table_path))
+    } else {
+        file_path.strip_suffix(".json").unwrap_or(file_path).to_string()
+    }
+}
+
+fn loot_table_lookup(
+    context: &LoadingContext,
+    table_id: &str,
+    visited: &mut HashSet<String>
+) -> Vec<String> {
+    if !visited.insert(table_id.to_string()) {
+        return Vec::new(); // Prevent circular references
+    }
+
+    let Some(table) = context.loot_table_map.get(table_id) else {
+        return Vec::new();
+    };
+
+    let mut result = table.items.clone();
+    for tag in &table.tags {
+        result.extend(tag_lookup(&context.tag_map, tag, &mut HashSet::new()));
+    }
+    for nested in &table.tables {
+        result.extend(loot_table_lookup(context, nested, visited));
+    }
+    result
+}
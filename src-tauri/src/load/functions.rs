@@ -0,0 +1,366 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use crate::load::{archive::*, strip_mc_prefix, Advancement};
+
+// triggers that never fire on their own, the datapack grants these with commands
+const COMMAND_TRIGGERS: [&str; 2] = ["impossible", "tick"];
+
+pub fn is_command_trigger(trigger: &str) -> bool {
+    let trigger = strip_mc_prefix(trigger);
+    COMMAND_TRIGGERS.contains(&trigger)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GrantAction {
+    Grant,
+    Revoke,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GrantMode {
+    Only,
+    From,
+    Through,
+    Until,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionCondition {
+    pub negated: bool, // unless
+    pub kind: String, // score, entity, block, predicate...
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionReference {
+    pub function: String,
+    pub mode: GrantMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub criterion: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<FunctionCondition>,
+}
+
+/// "reach this stat to be granted", from a function like
+/// execute if score @s bac_walk matches 100000000.. run advancement grant @s only blazeandcave:walk
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatGoal {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub criterion: Option<String>,
+    pub stat_type: String,
+    pub target: String,
+    pub value: i64,
+}
+
+#[derive(Debug, Clone)]
+struct AdvancementCommand {
+    action: GrantAction,
+    mode: GrantMode,
+    advancement: String,
+    criterion: Option<String>,
+    conditions: Vec<FunctionCondition>,
+}
+
+/// which functions grant or revoke which advancements
+#[derive(Debug, Default)]
+pub struct FunctionIndex {
+    commands: HashMap<String, Vec<AdvancementCommand>>, // function id, commands
+    objectives: HashMap<String, (String, String)>, // objective, stat type and stat
+}
+
+impl FunctionIndex {
+    pub fn from_archives(archives: &mut [Box<dyn Archive>]) -> Result<Self> {
+        let mut commands = HashMap::new();
+        let mut objectives = HashMap::new();
+        let categories = vec![FileCategory::Function];
+
+        for archive in archives {
+            for file_path in archive.list_files(&categories)? {
+                let Ok(content) = archive.read_file(&file_path) else { continue };
+                let parsed: Vec<AdvancementCommand> = content.lines().filter_map(parse_command).collect();
+                objectives.extend(content.lines().filter_map(parse_stat_objective));
+
+                // Later archives override earlier ones (datapack behavior)
+                commands.insert(extract_function_id(&file_path), parsed);
+            }
+        }
+
+        let index = FunctionIndex { commands, objectives };
+        println!("[LOAD] Found {} advancement commands in {} functions",
+                 index.commands.values().map(|c| c.len()).sum::<usize>(), index.commands.len());
+        Ok(index)
+    }
+
+    /// attach granting/revoking functions to advancements, following the tree for from/through/until
+    pub fn link(&self, advancements: &mut HashMap<String, Advancement>) {
+        let mut children: HashMap<String, Vec<String>> = HashMap::new();
+        for (id, advancement) in advancements.iter() {
+            if let Some(parent) = &advancement.parent {
+                children.entry(parent.clone()).or_default().push(id.clone());
+            }
+        }
+
+        let mut function_ids: Vec<&String> = self.commands.keys().collect();
+        function_ids.sort();
+
+        for function_id in function_ids {
+            for command in &self.commands[function_id] {
+                let reference = FunctionReference {
+                    function: function_id.clone(),
+                    mode: command.mode,
+                    criterion: command.criterion.clone(),
+                    conditions: command.conditions.clone(),
+                };
+
+                let stat_goals = self.stat_goals(command);
+                for target in affected_advancements(command, advancements, &children) {
+                    let Some(advancement) = advancements.get_mut(&target) else { continue };
+                    match command.action {
+                        GrantAction::Grant => {
+                            advancement.granted_by.push(reference.clone());
+                            for goal in &stat_goals {
+                                if !advancement.stat_goals.contains(goal) {
+                                    advancement.stat_goals.push(goal.clone());
+                                }
+                            }
+                        }
+                        GrantAction::Revoke => advancement.revoked_by.push(reference.clone()),
+                    }
+                }
+            }
+        }
+    }
+
+    /// score conditions on objectives that track a statistic
+    fn stat_goals(&self, command: &AdvancementCommand) -> Vec<StatGoal> {
+        if command.action != GrantAction::Grant {
+            return Vec::new();
+        }
+
+        command.conditions.iter()
+            .filter(|condition| condition.kind == "score" && !condition.negated)
+            .filter_map(|condition| {
+                let [_, objective, op, range] = condition.args.as_slice() else { return None };
+                if op != "matches" { return None }
+                let (stat_type, target) = self.objectives.get(objective)?;
+                Some(StatGoal {
+                    criterion: command.criterion.clone(),
+                    stat_type: stat_type.clone(),
+                    target: target.clone(),
+                    value: range_min(range)?,
+                })
+            })
+            .collect()
+    }
+}
+
+// "100.." -> 100, "5" -> 5, "..10" has no goal
+fn range_min(range: &str) -> Option<i64> {
+    let min = range.split("..").next()?;
+    min.parse().ok()
+}
+
+fn affected_advancements(
+    command: &AdvancementCommand,
+    advancements: &HashMap<String, Advancement>,
+    children: &HashMap<String, Vec<String>>
+) -> Vec<String> {
+    let mut affected = vec![command.advancement.clone()];
+
+    if matches!(command.mode, GrantMode::Until | GrantMode::Through) {
+        let mut visited = HashSet::new();
+        let mut current = advancements.get(&command.advancement).and_then(|adv| adv.parent.clone());
+        while let Some(id) = current {
+            if !visited.insert(id.clone()) { break }
+            current = advancements.get(&id).and_then(|adv| adv.parent.clone());
+            affected.push(id);
+        }
+    }
+
+    if matches!(command.mode, GrantMode::From | GrantMode::Through) {
+        let mut visited = HashSet::new();
+        let mut stack = vec![command.advancement.clone()];
+        while let Some(id) = stack.pop() {
+            for child in children.get(&id).into_iter().flatten() {
+                if visited.insert(child.clone()) {
+                    affected.push(child.clone());
+                    stack.push(child.clone());
+                }
+            }
+        }
+    }
+
+    affected
+}
+
+/// get function id from path
+fn extract_function_id(file_path: &str) -> String {
+    // "data/blazeandcave/function/rewards/trophy.mcfunction" -> "blazeandcave:rewards/trophy"
+    let parts: Vec<&str> = file_path.split('/').collect();
+    if parts.len() >= 4 && parts[0] == "data" && parts[2] == "function" {
+        let function_path = parts[3..].join("/");
+        format!("{}:{}", parts[1], function_path.strip_suffix(".mcfunction").unwrap_or(&function_path))
+    } else {
+        file_path.strip_suffix(".mcfunction").unwrap_or(file_path).to_string()
+    }
+}
+
+// ===== COMMAND PARSING =====
+
+/*
+    advancement grant @s only blazeandcave:bacap/root
+    execute if score @s bac_obtained matches 10.. run advancement grant @s only blazeandcave:x crit
+    $advancement revoke @s through $(adv)
+*/
+fn parse_command(line: &str) -> Option<AdvancementCommand> {
+    let line = line.trim();
+    let line = line.strip_prefix('$').unwrap_or(line); // macro lines
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let tokens = tokenize(line);
+    let mut conditions = Vec::new();
+    let mut i = 0;
+
+    if tokens.first().map(String::as_str) == Some("execute") {
+        i = 1;
+        while i < tokens.len() && tokens[i] != "run" {
+            let negated = match tokens[i].as_str() {
+                "if" => false,
+                "unless" => true,
+                _ => {
+                    i += 1;
+                    continue;
+                }
+            };
+            let kind = tokens.get(i + 1)?.clone();
+            let arg_count = condition_arg_count(&kind, &tokens[i + 2..]);
+            let end = (i + 2 + arg_count).min(tokens.len());
+            conditions.push(FunctionCondition {
+                negated,
+                kind,
+                args: tokens[i + 2..end].to_vec(),
+            });
+            i = end;
+        }
+        i += 1; // run
+    }
+
+    let rest = tokens.get(i..)?;
+    if rest.first().map(String::as_str) != Some("advancement") {
+        return None;
+    }
+
+    let action = match rest.get(1)?.as_str() {
+        "grant" => GrantAction::Grant,
+        "revoke" => GrantAction::Revoke,
+        _ => return None,
+    };
+    let mode = match rest.get(3)?.as_str() {
+        "only" => GrantMode::Only,
+        "from" => GrantMode::From,
+        "through" => GrantMode::Through,
+        "until" => GrantMode::Until,
+        _ => return None, // everything
+    };
+    let advancement = strip_mc_prefix(rest.get(4)?).to_string();
+    // macro arguments can't be resolved statically
+    if advancement.contains("$(") {
+        return None;
+    }
+    let criterion = if mode == GrantMode::Only {
+        rest.get(5).map(|c| strip_mc_prefix(c).to_string())
+    } else {
+        None
+    };
+
+    Some(AdvancementCommand { action, mode, advancement, criterion, conditions })
+}
+
+/*
+    scoreboard objectives add bac_walk minecraft.custom:minecraft.walk_one_cm
+*/
+fn parse_stat_objective(line: &str) -> Option<(String, (String, String))> {
+    let tokens = tokenize(line.trim());
+    let [scoreboard, objectives, add, name, criterion, ..] = tokens.as_slice() else { return None };
+    if scoreboard != "scoreboard" || objectives != "objectives" || add != "add" {
+        return None;
+    }
+
+    // stat criteria are "namespace.type:namespace.stat", everything else is dummy, trigger, health...
+    let (stat_type, stat) = criterion.split_once(':')?;
+    let stat_type = stat_type.split_once('.').map_or(stat_type, |(_, stat_type)| stat_type);
+    let stat = stat.split_once('.').map_or(stat, |(_, stat)| stat);
+    Some((name.clone(), (stat_type.to_string(), stat.to_string())))
+}
+
+/// how many tokens an execute if/unless subcommand consumes
+fn condition_arg_count(kind: &str, args: &[String]) -> usize {
+    match kind {
+        "entity" | "predicate" | "function" | "dimension" => 1,
+        "loaded" => 3, // x y z
+        "biome" | "block" => 4, // x y z id
+        "blocks" => 10,
+        "items" => match args.first().map(String::as_str) {
+            Some("block") => 6, // block x y z slots predicate
+            _ => 4, // entity target slots predicate
+        },
+        "score" => match args.get(2).map(String::as_str) {
+            Some("matches") => 4,
+            _ => 5, // target objective op source objective
+        },
+        "data" => match args.first().map(String::as_str) {
+            Some("block") => 5,
+            _ => 3, // entity|storage source path
+        },
+        _ => 0,
+    }
+}
+
+/// split on whitespace, keeping selectors, nbt and quoted strings in one piece
+fn tokenize(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+
+    for ch in line.chars() {
+        match quote {
+            Some(q) => {
+                if ch == q { quote = None }
+                current.push(ch);
+            }
+            None => match ch {
+                '"' | '\'' => {
+                    quote = Some(ch);
+                    current.push(ch);
+                }
+                '[' | '{' | '(' => {
+                    depth += 1;
+                    current.push(ch);
+                }
+                ']' | '}' | ')' => {
+                    depth -= 1;
+                    current.push(ch);
+                }
+                c if c.is_whitespace() && depth <= 0 => {
+                    if !current.is_empty() {
+                        tokens.push(std::mem::take(&mut current));
+                    }
+                }
+                _ => current.push(ch),
+            },
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
@@ -0,0 +1,307 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use crate::{load::Advancement, structs::AdvancementCategory};
+
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeLayout {
+    pub nodes: Vec<LayoutNode>, // pre-order, siblings sorted by key
+    pub edges: Vec<LayoutEdge>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutNode {
+    pub key: String,
+    pub x: i32,
+    pub y: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutEdge {
+    pub parent: String,
+    pub child: String,
+}
+
+/// lay out every category the way the in-game advancement screen does
+pub fn assign_layouts(advancements: &HashMap<String, Advancement>, categories: &mut HashMap<String, AdvancementCategory>) {
+    let children = sorted_children(advancements, categories);
+    for (key, category) in categories.iter_mut() {
+        category.layout = TreePositioner::run(key, &children);
+    }
+}
+
+/// deterministic sibling order. advancements with a missing parent hang off their category root
+fn sorted_children(
+    advancements: &HashMap<String, Advancement>,
+    categories: &HashMap<String, AdvancementCategory>
+) -> HashMap<String, Vec<String>> {
+    let mut children: HashMap<String, Vec<String>> = HashMap::new();
+    for (id, advancement) in advancements {
+        if categories.contains_key(id) {
+            continue;
+        }
+        let parent = match &advancement.parent {
+            Some(parent) if advancements.contains_key(parent) => parent.clone(),
+            _ => advancement.category.clone(),
+        };
+        children.entry(parent).or_default().push(id.clone());
+    }
+
+    for siblings in children.values_mut() {
+        siblings.sort();
+    }
+    children
+}
+
+// ===== TREE NODE POSITION =====
+// port of minecraft's TreeNodePosition (Buchheim/Walker), nodes live in an arena
+
+struct Node {
+    key: String,
+    parent: Option<usize>,
+    previous_sibling: Option<usize>,
+    child_index: i32,
+    children: Vec<usize>,
+    ancestor: usize,
+    thread: Option<usize>,
+    x: i32,
+    y: f32,
+    modifier: f32,
+    change: f32,
+    shift: f32,
+}
+
+struct TreePositioner<'a> {
+    nodes: Vec<Node>,
+    children: &'a HashMap<String, Vec<String>>,
+    visited: HashSet<String>,
+}
+
+impl TreePositioner<'_> {
+    fn run(root: &str, children: &HashMap<String, Vec<String>>) -> TreeLayout {
+        let mut tree = TreePositioner { nodes: Vec::new(), children, visited: HashSet::new() };
+        let root_index = tree.add_node(root, None, None, 1, 0);
+
+        tree.first_walk(root_index);
+        let root_y = tree.nodes[root_index].y;
+        let min_y = tree.second_walk(root_index, 0.0, 0, root_y);
+        if min_y < 0.0 {
+            tree.third_walk(root_index, -min_y);
+        }
+
+        tree.into_layout()
+    }
+
+    fn add_node(&mut self, key: &str, parent: Option<usize>, previous_sibling: Option<usize>, child_index: i32, x: i32) -> usize {
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            key: key.to_string(),
+            parent, previous_sibling, child_index,
+            children: Vec::new(),
+            ancestor: index,
+            thread: None,
+            x,
+            y: -1.0,
+            modifier: 0.0,
+            change: 0.0,
+            shift: 0.0,
+        });
+        self.visited.insert(key.to_string());
+
+        let mut previous = None;
+        for child in self.children.get(key).into_iter().flatten() {
+            if self.visited.contains(child) { continue } // cycles
+            let child_index = self.nodes[index].children.len() as i32 + 1;
+            let child_node = self.add_node(child, Some(index), previous, child_index, x + 1);
+            self.nodes[index].children.push(child_node);
+            previous = Some(child_node);
+        }
+        index
+    }
+
+    fn first_walk(&mut self, v: usize) {
+        let children = self.nodes[v].children.clone();
+        let previous_sibling = self.nodes[v].previous_sibling;
+
+        if children.is_empty() {
+            self.nodes[v].y = previous_sibling.map_or(0.0, |p| self.nodes[p].y + 1.0);
+            return;
+        }
+
+        let mut default_ancestor = None;
+        for &child in &children {
+            self.first_walk(child);
+            default_ancestor = Some(self.apportion(child, default_ancestor.unwrap_or(child)));
+        }
+        self.execute_shifts(v);
+
+        let midpoint = (self.nodes[children[0]].y + self.nodes[children[children.len() - 1]].y) / 2.0;
+        match previous_sibling {
+            Some(p) => {
+                self.nodes[v].y = self.nodes[p].y + 1.0;
+                self.nodes[v].modifier = self.nodes[v].y - midpoint;
+            }
+            None => self.nodes[v].y = midpoint,
+        }
+    }
+
+    fn second_walk(&mut self, v: usize, offset_y: f32, column_x: i32, mut min_y: f32) -> f32 {
+        self.nodes[v].y += offset_y;
+        self.nodes[v].x = column_x;
+        min_y = min_y.min(self.nodes[v].y);
+
+        let modifier = self.nodes[v].modifier;
+        for child in self.nodes[v].children.clone() {
+            min_y = self.second_walk(child, offset_y + modifier, column_x + 1, min_y);
+        }
+        min_y
+    }
+
+    fn third_walk(&mut self, v: usize, offset_y: f32) {
+        self.nodes[v].y += offset_y;
+        for child in self.nodes[v].children.clone() {
+            self.third_walk(child, offset_y);
+        }
+    }
+
+    fn execute_shifts(&mut self, v: usize) {
+        let mut shift = 0.0;
+        let mut change = 0.0;
+        for &child in self.nodes[v].children.clone().iter().rev() {
+            let node = &mut self.nodes[child];
+            node.y += shift;
+            node.modifier += shift;
+            change += node.change;
+            shift += node.shift + change;
+        }
+    }
+
+    fn previous_or_thread(&self, v: usize) -> Option<usize> {
+        self.nodes[v].thread.or_else(|| self.nodes[v].children.first().copied())
+    }
+
+    fn next_or_thread(&self, v: usize) -> Option<usize> {
+        self.nodes[v].thread.or_else(|| self.nodes[v].children.last().copied())
+    }
+
+    fn apportion(&mut self, v: usize, mut default_ancestor: usize) -> usize {
+        let (Some(previous_sibling), Some(parent)) = (self.nodes[v].previous_sibling, self.nodes[v].parent) else {
+            return default_ancestor;
+        };
+
+        let mut inside_right = v;
+        let mut outside_right = v;
+        let mut inside_left = previous_sibling;
+        let mut outside_left = self.nodes[parent].children[0];
+        let mut sum_inside_right = self.nodes[v].modifier;
+        let mut sum_outside_right = self.nodes[v].modifier;
+        let mut sum_inside_left = self.nodes[inside_left].modifier;
+        let mut sum_outside_left = self.nodes[outside_left].modifier;
+
+        while let (Some(next_left), Some(next_right)) = (self.next_or_thread(inside_left), self.previous_or_thread(inside_right)) {
+            inside_left = next_left;
+            inside_right = next_right;
+            outside_left = self.previous_or_thread(outside_left).unwrap_or(outside_left);
+            outside_right = self.next_or_thread(outside_right).unwrap_or(outside_right);
+            self.nodes[outside_right].ancestor = v;
+
+            let shift = self.nodes[inside_left].y + sum_inside_left - (self.nodes[inside_right].y + sum_inside_right) + 1.0;
+            if shift > 0.0 {
+                let ancestor = self.get_ancestor(inside_left, v, default_ancestor);
+                self.move_subtree(ancestor, v, shift);
+                sum_inside_right += shift;
+                sum_outside_right += shift;
+            }
+
+            sum_inside_left += self.nodes[inside_left].modifier;
+            sum_inside_right += self.nodes[inside_right].modifier;
+            sum_outside_left += self.nodes[outside_left].modifier;
+            sum_outside_right += self.nodes[outside_right].modifier;
+        }
+
+        if let (Some(next_left), None) = (self.next_or_thread(inside_left), self.next_or_thread(outside_right)) {
+            self.nodes[outside_right].thread = Some(next_left);
+            self.nodes[outside_right].modifier += sum_inside_left - sum_outside_right;
+        } else {
+            if let (Some(next_right), None) = (self.previous_or_thread(inside_right), self.previous_or_thread(outside_left)) {
+                self.nodes[outside_left].thread = Some(next_right);
+                self.nodes[outside_left].modifier += sum_inside_right - sum_outside_left;
+            }
+            default_ancestor = v;
+        }
+        default_ancestor
+    }
+
+    fn move_subtree(&mut self, from: usize, to: usize, shift: f32) {
+        let subtrees = (self.nodes[to].child_index - self.nodes[from].child_index) as f32;
+        if subtrees != 0.0 {
+            self.nodes[to].change -= shift / subtrees;
+            self.nodes[from].change += shift / subtrees;
+        }
+        let node = &mut self.nodes[to];
+        node.shift += shift;
+        node.y += shift;
+        node.modifier += shift;
+    }
+
+    fn get_ancestor(&self, v: usize, other: usize, default_ancestor: usize) -> usize {
+        let ancestor = self.nodes[v].ancestor;
+        let siblings = self.nodes[other].parent.map(|p| &self.nodes[p].children);
+        if siblings.is_some_and(|s| s.contains(&ancestor)) {
+            ancestor
+        } else {
+            default_ancestor
+        }
+    }
+
+    fn into_layout(self) -> TreeLayout {
+        let mut layout = TreeLayout::default();
+        for node in &self.nodes {
+            layout.nodes.push(LayoutNode { key: node.key.clone(), x: node.x, y: node.y });
+            if let Some(parent) = node.parent {
+                layout.edges.push(LayoutEdge { parent: self.nodes[parent].key.clone(), child: node.key.clone() });
+            }
+        }
+        layout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(edges: &[(&str, &[&str])]) -> Vec<(String, i32, f32)> {
+        let children = edges.iter()
+            .map(|(parent, kids)| (parent.to_string(), kids.iter().map(|kid| kid.to_string()).collect()))
+            .collect();
+        TreePositioner::run("root", &children).nodes.into_iter()
+            .map(|node| (node.key, node.x, node.y))
+            .collect()
+    }
+
+    fn expected(nodes: &[(&str, i32, f32)]) -> Vec<(String, i32, f32)> {
+        nodes.iter().map(|(key, x, y)| (key.to_string(), *x, *y)).collect()
+    }
+
+    #[test]
+    fn leaf_sibling_threads_past_subtree() {
+        let layout = positions(&[("root", &["a", "b"]), ("a", &["a1", "a2"])]);
+        assert_eq!(layout, expected(&[
+            ("root", 0, 1.0),
+            ("a", 1, 0.5), ("a1", 2, 0.0), ("a2", 2, 1.0),
+            ("b", 1, 1.5),
+        ]));
+    }
+
+    #[test]
+    fn overlapping_subtrees_get_shifted_apart() {
+        let layout = positions(&[("root", &["a", "b"]), ("a", &["a1", "a2"]), ("b", &["b1", "b2"])]);
+        assert_eq!(layout, expected(&[
+            ("root", 0, 1.5),
+            ("a", 1, 0.5), ("a1", 2, 0.0), ("a2", 2, 1.0),
+            ("b", 1, 2.5), ("b1", 2, 2.0), ("b2", 2, 3.0),
+        ]));
+    }
+}
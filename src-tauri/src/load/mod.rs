@@ -4,6 +4,22 @@ pub mod advancements;
 pub use advancements::*;
 pub mod world;
 pub use world::*;
+pub mod functions;
+pub use functions::*;
+pub mod layout;
+pub use layout::*;
+pub mod worldgen;
+pub use worldgen::*;
+pub mod subjects;
+pub use subjects::*;
+pub mod scoreboard;
+pub use scoreboard::*;
+pub mod playerdata;
+pub use playerdata::*;
+pub mod regions;
+pub use regions::*;
+pub mod server;
+pub use server::*;
 mod context;
 use context::*;
 pub mod archive;
@@ -21,38 +37,74 @@ pub fn strip_mc_prefix(s: &str) -> &str {
     s.strip_prefix("minecraft:").unwrap_or(s)
 }
 
-pub async fn load() -> Result<Data> {
+// singleplayer world folder, or a server folder with server.properties
+pub const WORLD_PATH: &str = "C:\\Users\\Sebastian\\AppData\\Roaming\\ModrinthApp\\profiles\\My BAC pack\\saves\\New World";
+
+pub async fn load(config: &crate::config::Config) -> Result<Data> {
     // these will be obtained somehow
     let minecraft_jar_path = Path::new("C:\\Users\\Sebastian\\AppData\\Roaming\\ModrinthApp\\meta\\versions\\1.21.5-21.5.75\\1.21.5-21.5.75.jar");
-    let world_path = Path::new("C:\\Users\\Sebastian\\AppData\\Roaming\\ModrinthApp\\profiles\\My BAC pack\\saves\\New World");
+    let world_path = Path::new(WORLD_PATH);
+    let layout = WorldLayout::resolve(world_path, config.world_layout)?;
+
+    let world = world::read(&layout)?;
+    let scoreboard = read_scoreboard(&layout.main).unwrap_or_else(|e| {
+        println!("[WARN] Failed to read scoreboard: {:?}", e);
+        Scoreboard::default()
+    });
+    let (mut players, mut advancement_progress) = world::read_players(&layout)?;
 
-    let world = world::read(world_path)?;
-    let (mut players, advancement_progress) = world::read_players(world_path)?;
+    let local_names = local_names(&layout, players.keys());
 
     // grab user names and faces, tries to fetch them if we don't have them
     let cache = Cache::new().await?;
     let profile_task = {
         let cache = cache.clone();
         let player_uuids: Vec<String> = players.keys().cloned().collect();
+        let local_names = local_names.clone();
         tokio::spawn(async move {
             for uuid in player_uuids {
-                if let Some((_name, _avatar_url)) = cache.get_cached_or_fetch(&uuid).await {
+                let local_name = local_names.get(&uuid).map(String::as_str);
+                if let Some((_name, _avatar_url)) = cache.get_cached_or_fetch(&uuid, local_name).await {
                     // Cache automatically updates as each completes
                 }
             }
         })
     };
 
-    let mut advancements = load_all_advancements(minecraft_jar_path, world_path, &world.enabled_datapacks)?;
+    let (mut advancements, worldgen, subjects) = load_all_advancements(minecraft_jar_path, &layout.datapack_dirs(), &world.enabled_datapacks)?;
+
+    // player head icons get rendered into cached/heads as their skins come in
+    let head_textures: HashSet<String> = advancements.values()
+        .filter_map(|advancement| match &advancement.icon {
+            Icon::PlayerHead { texture_id } => Some(texture_id.clone()),
+            _ => None,
+        })
+        .collect();
+    tokio::spawn(async move {
+        for texture_id in head_textures {
+            if let Err(e) = crate::cache::cache_player_head(&texture_id).await {
+                eprintln!("[CACHE] Failed to render head {}: {}", texture_id, e);
+            }
+        }
+    });
 
     let (spreadsheet_data, classes) = load_spreadsheet()?;
     assign_spreadsheet_info(&mut advancements, &spreadsheet_data);
-    let categories = assign_categories(&mut advancements);
+    let mut categories = assign_categories(&mut advancements);
+    assign_layouts(&advancements, &mut categories);
+
+    for (key, player_progress) in advancement_progress.iter_mut() {
+        for progress in player_progress.values_mut() {
+            progress.completed_at = completion_time(advancements.get(key), progress);
+        }
+    }
 
     for (uuid, player) in players.iter_mut() {
         if let Some(profile) = cache.get_player(uuid).await {
             player.name = Some(profile.name);
             player.avatar_url = Some(format!("data:image/png;base64,{}", profile.face));
+        } else {
+            player.name = local_names.get(uuid).cloned();
         }
     }
 
@@ -65,6 +117,10 @@ pub async fn load() -> Result<Data> {
         categories,
         classes,
         progress: advancement_progress,
+        worldgen,
+        subjects,
+        scoreboard,
+        locations: LocationIndex::default(),
     })
 }
 
@@ -72,6 +128,25 @@ pub async fn load() -> Result<Data> {
 
 
 
+/// names we can get without asking mojang: usercache.json first, then what bukkit left in playerdata
+fn local_names<'a>(layout: &WorldLayout, uuids: impl Iterator<Item = &'a String>) -> HashMap<String, String> {
+    let mut names = layout.usercache()
+        .map(|path| read_usercache(&path).unwrap_or_else(|e| {
+            println!("[WARN] Failed to read usercache: {:?}", e);
+            HashMap::new()
+        }))
+        .unwrap_or_default();
+
+    for uuid in uuids {
+        if names.contains_key(uuid) { continue }
+        let playerdata_path = layout.playerdata.join(format!("{}.dat", uuid));
+        if let Ok(Some(name)) = read_last_known_name(&playerdata_path) {
+            names.insert(uuid.clone(), name);
+        }
+    }
+    names
+}
+
 fn load_spreadsheet() -> Result<(HashMap<String, SpreadsheetInfo>, Vec<String>)> {
     #[derive(Debug, Deserialize)]
     struct CsvRow {
@@ -81,6 +156,8 @@ fn load_spreadsheet() -> Result<(HashMap<String, SpreadsheetInfo>, Vec<String>)>
         class: String,
         #[serde(rename = "Actual Requirements (if different)")]
         actual_requirements: String,
+        #[serde(rename = "Time", default)]
+        time: String,
     }
 
     let spreadsheet_path = "spreadsheet_list.csv";
@@ -114,6 +191,7 @@ fn load_spreadsheet() -> Result<(HashMap<String, SpreadsheetInfo>, Vec<String>)>
             SpreadsheetInfo {
                 class: class.to_string(),
                 requirement_details: requirement_details,
+                time: Some(row.time).filter(|time| !time.is_empty()),
             },
         );
     }
@@ -142,11 +220,16 @@ fn assign_categories(advancements: &mut HashMap<String, Advancement>) -> HashMap
                     key: id.clone(),
                     display_name: advancement.display_name.clone(),
                     icon: advancement.icon.clone(),
+                    background: advancement.background.clone(),
+                    layout: TreeLayout::default(), // later
                 },
             );
         }
     }
 
+    // orphans go to the first category by key, so it's the same every load
+    let fallback_category = categories.keys().min().cloned();
+
     let all_ids: Vec<String> = advancements.keys().cloned().collect();
     for id in all_ids {
         let mut current_id_opt: Option<String> = Some(id.clone());
@@ -172,8 +255,8 @@ fn assign_categories(advancements: &mut HashMap<String, Advancement>) -> HashMap
         if let Some(advancement_to_update) = advancements.get_mut(&id) {
             if let Some(cat_id) = found_category_id {
                 advancement_to_update.category = cat_id;
-            } else {
-                advancement_to_update.category = categories.keys().next().unwrap().clone();
+            } else if let Some(cat_id) = &fallback_category {
+                advancement_to_update.category = cat_id.clone();
             }
         }
     }
@@ -0,0 +1,123 @@
+use anyhow::Result;
+use crab_nbt::{NbtCompound, NbtTag};
+use serde::Serialize;
+use std::path::Path;
+use crate::load::{strip_mc_prefix, world::*};
+
+// 1.21.5 moved armor and offhand out of Inventory, these are the old slot numbers for them
+const EQUIPMENT_SLOTS: [(&str, i32); 5] = [("feet", 100), ("legs", 101), ("chest", 102), ("head", 103), ("offhand", -106)];
+
+/// what's in playerdata/<uuid>.dat
+#[derive(Debug, Clone, Serialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerState {
+    pub position: [f64; 3],
+    pub dimension: String,
+    pub health: f64,
+    pub food_level: i32,
+    pub xp_level: i32,
+    pub xp_total: i32,
+    pub inventory: Vec<ItemStack>,
+    pub ender_chest: Vec<ItemStack>,
+    pub effects: Vec<ActiveEffect>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemStack {
+    pub slot: i32,
+    pub id: String,
+    pub count: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub components: Option<serde_json::Value>, // enchantments, names, shulker contents...
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveEffect {
+    pub id: String,
+    pub amplifier: i32,
+    pub duration: i32, // ticks, -1 is infinite
+}
+
+/// bukkit servers remember the name, handy when nothing else knows an offline uuid
+pub fn read_last_known_name(path: &Path) -> Result<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let nbt_data = read_nbt(path)?;
+    Ok(nbt_data.root_tag.get_compound("bukkit")
+        .and_then(|bukkit| bukkit.get("lastKnownName"))
+        .and_then(nbt_str)
+        .map(str::to_string))
+}
+
+pub fn read_player_state(path: &Path) -> Result<Option<PlayerState>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let nbt_data = read_nbt(path)?;
+    let root = &nbt_data.root_tag;
+    let number = |name: &str| root.get(name).and_then(nbt_number);
+
+    let mut position = [0.0; 3];
+    for (i, value) in root.get_list("Pos").into_iter().flatten().take(3).enumerate() {
+        position[i] = nbt_number(value).unwrap_or_default();
+    }
+
+    let mut inventory = item_list(root.get_list("Inventory"));
+    if let Some(equipment) = root.get_compound("equipment") {
+        for (name, slot) in EQUIPMENT_SLOTS {
+            if let Some(NbtTag::Compound(item)) = equipment.get(name) {
+                inventory.extend(item_stack(item, Some(slot)));
+            }
+        }
+    }
+
+    let effects = root.get_list("active_effects").into_iter().flatten()
+        .filter_map(|tag| match tag {
+            NbtTag::Compound(effect) => Some(ActiveEffect {
+                id: strip_mc_prefix(effect.get("id").and_then(nbt_str)?).to_string(),
+                amplifier: effect.get("amplifier").and_then(nbt_number).unwrap_or_default() as i32,
+                duration: effect.get("duration").and_then(nbt_number).unwrap_or_default() as i32,
+            }),
+            _ => None,
+        })
+        .collect();
+
+    Ok(Some(PlayerState {
+        position,
+        dimension: strip_mc_prefix(root.get("Dimension").and_then(nbt_str).unwrap_or("overworld")).to_string(),
+        health: number("Health").unwrap_or_default(),
+        food_level: number("foodLevel").unwrap_or_default() as i32,
+        xp_level: number("XpLevel").unwrap_or_default() as i32,
+        xp_total: number("XpTotal").unwrap_or_default() as i32,
+        inventory,
+        ender_chest: item_list(root.get_list("EnderItems")),
+        effects,
+    }))
+}
+
+fn item_list(list: Option<&Vec<NbtTag>>) -> Vec<ItemStack> {
+    list.into_iter().flatten()
+        .filter_map(|tag| match tag {
+            NbtTag::Compound(item) => item_stack(item, None),
+            _ => None,
+        })
+        .collect()
+}
+
+/*
+    { Slot: 0b, id: "minecraft:shulker_box", count: 1, components: { "minecraft:container": [...] } }
+*/
+fn item_stack(item: &NbtCompound, slot: Option<i32>) -> Option<ItemStack> {
+    let count = item.get("count").or_else(|| item.get("Count")).and_then(nbt_number).unwrap_or(1.0);
+    Some(ItemStack {
+        slot: slot.or_else(|| item.get("Slot").and_then(nbt_number).map(|slot| slot as i32)).unwrap_or_default(),
+        id: strip_mc_prefix(item.get("id").and_then(nbt_str)?).to_string(),
+        count: count as i32,
+        components: item.get("components").map(nbt_to_json),
+    })
+}
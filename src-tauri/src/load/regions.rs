@@ -0,0 +1,119 @@
+use anyhow::{Context, Result};
+use crab_nbt::NbtTag;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use std::{collections::HashMap, fs, io::Read, path::Path};
+use crate::load::{strip_mc_prefix, world::nbt_str, world::nbt_number, WorldLayout};
+
+const SECTOR_BYTES: usize = 4096;
+
+/// generated structures and biomes per dimension, from the region files
+#[derive(Debug, Default)]
+pub struct LocationIndex {
+    pub dimensions: HashMap<String, DimensionLocations>,
+}
+
+#[derive(Debug, Default)]
+pub struct DimensionLocations {
+    pub structures: HashMap<String, Vec<[i32; 2]>>, // block x, z of each start
+    pub biomes: HashMap<String, Vec<[i32; 2]>>, // block x, z of each chunk center containing it
+}
+
+pub fn scan_regions(layout: &WorldLayout) -> Result<LocationIndex> {
+    let mut index = LocationIndex::default();
+    let mut chunks = 0;
+
+    for (dimension, dir) in layout.region_dirs() {
+        let locations = index.dimensions.entry(dimension).or_default();
+        for entry in fs::read_dir(&dir)?.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            if path.extension().map_or(true, |ext| ext != "mca") {
+                continue;
+            }
+            match scan_region_file(&path, locations) {
+                Ok(count) => chunks += count,
+                Err(e) => println!("[WARN] Failed to scan {}: {:?}", path.display(), e),
+            }
+        }
+    }
+
+    println!("[LOAD] Scanned {} chunks in {} dimensions", chunks, index.dimensions.len());
+    Ok(index)
+}
+
+/*
+    anvil: 1024 x (3 byte sector offset, 1 byte sector count), 1024 timestamps, then
+    each chunk is a 4 byte length, 1 byte compression type and the compressed nbt
+*/
+fn scan_region_file(path: &Path, locations: &mut DimensionLocations) -> Result<usize> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    if bytes.len() < SECTOR_BYTES * 2 {
+        return Ok(0); // empty region
+    }
+
+    let mut chunks = 0;
+    for i in 0..1024 {
+        let header = &bytes[i * 4..i * 4 + 4];
+        let offset = ((header[0] as usize) << 16 | (header[1] as usize) << 8 | header[2] as usize) * SECTOR_BYTES;
+        if offset == 0 || offset + 5 > bytes.len() {
+            continue;
+        }
+
+        let length = u32::from_be_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]) as usize;
+        let Some(data) = bytes.get(offset + 5..offset + 4 + length) else { continue };
+        let Some(chunk) = decompress(bytes[offset + 4], data) else { continue };
+
+        let mut cursor = std::io::Cursor::new(chunk);
+        let Ok(nbt_data) = crab_nbt::Nbt::read(&mut cursor) else { continue };
+        read_chunk(&nbt_data.root_tag, locations);
+        chunks += 1;
+    }
+    Ok(chunks)
+}
+
+fn decompress(compression: u8, data: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    match compression {
+        1 => GzDecoder::new(data).read_to_end(&mut out).ok()?,
+        2 => ZlibDecoder::new(data).read_to_end(&mut out).ok()?,
+        3 => return Some(data.to_vec()),
+        _ => return None, // lz4 and external .mcc chunks
+    };
+    Some(out)
+}
+
+/*
+    { xPos: 3, zPos: -2, Status: "minecraft:full",
+      sections: [{ biomes: { palette: ["minecraft:plains", ...] } }],
+      structures: { starts: { "minecraft:village_plains": { id: "...", ChunkX: 3, ChunkZ: -2 } } } }
+*/
+fn read_chunk(chunk: &crab_nbt::NbtCompound, locations: &mut DimensionLocations) {
+    let (Some(chunk_x), Some(chunk_z)) = (chunk.get("xPos").and_then(nbt_number), chunk.get("zPos").and_then(nbt_number)) else { return };
+    let center = [chunk_x as i32 * 16 + 8, chunk_z as i32 * 16 + 8];
+
+    let mut biomes: Vec<&str> = Vec::new();
+    for section in chunk.get_list("sections").into_iter().flatten() {
+        let NbtTag::Compound(section) = section else { continue };
+        let palette = section.get_compound("biomes").and_then(|biomes| biomes.get_list("palette"));
+        for biome in palette.into_iter().flatten().filter_map(nbt_str) {
+            if !biomes.contains(&biome) {
+                biomes.push(biome);
+            }
+        }
+    }
+    for biome in biomes {
+        locations.biomes.entry(strip_mc_prefix(biome).to_string()).or_default().push(center);
+    }
+
+    let starts = chunk.get_compound("structures").and_then(|structures| structures.get_compound("starts"));
+    for (name, start) in starts.map(|starts| starts.child_tags.iter()).into_iter().flatten() {
+        let NbtTag::Compound(start) = start else { continue };
+        if start.get("id").and_then(nbt_str) == Some("INVALID") {
+            continue;
+        }
+        let position = match (start.get("ChunkX").and_then(nbt_number), start.get("ChunkZ").and_then(nbt_number)) {
+            (Some(x), Some(z)) => [x as i32 * 16 + 8, z as i32 * 16 + 8],
+            _ => center,
+        };
+        locations.structures.entry(strip_mc_prefix(name).to_string()).or_default().push(position);
+    }
+}
@@ -0,0 +1,107 @@
+use anyhow::{Context, Result};
+use crab_nbt::NbtTag;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::Path};
+use crate::load::world::{nbt_number, nbt_str, read_nbt};
+
+pub const SCOREBOARD_FILE: &str = "scoreboard.dat";
+
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Objective {
+    pub name: String,
+    pub criteria: String, // dummy, or a stat like minecraft.custom:minecraft.walk_one_cm
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>, // text component json
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Scoreboard {
+    pub objectives: HashMap<String, Objective>,
+    pub scores: HashMap<String, HashMap<String, i32>>, // holder (player name or entity uuid), objective, score
+}
+
+/// config entry turning an objective into a progress bar for an advancement
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectiveGoal {
+    pub objective: String,
+    pub advancement: String,
+    pub target: i32,
+}
+
+/*
+    data: {
+        Objectives: [{ Name: "bac_obtained", CriteriaName: "dummy", DisplayName: '"..."' }],
+        PlayerScores: [{ Name: "Steve", Objective: "bac_obtained", Score: 12 }]
+    }
+*/
+pub fn read_scoreboard(world_path: &Path) -> Result<Scoreboard> {
+    let path = world_path.join("data").join(SCOREBOARD_FILE);
+    if !path.exists() {
+        return Ok(Scoreboard::default());
+    }
+
+    let nbt_data = read_nbt(&path)?;
+    let data = nbt_data.root_tag.get_compound("data").with_context(|| "Missing data compound")?;
+    let mut scoreboard = Scoreboard::default();
+
+    for tag in data.get_list("Objectives").into_iter().flatten() {
+        let NbtTag::Compound(objective) = tag else { continue };
+        let Some(name) = objective.get("Name").and_then(nbt_str).map(str::to_string) else { continue };
+        scoreboard.objectives.insert(name.clone(), Objective {
+            name,
+            criteria: objective.get("CriteriaName").and_then(nbt_str).map(str::to_string).unwrap_or_default(),
+            display_name: objective.get("DisplayName").and_then(nbt_str).map(str::to_string),
+        });
+    }
+
+    for tag in data.get_list("PlayerScores").into_iter().flatten() {
+        let NbtTag::Compound(score) = tag else { continue };
+        let (Some(holder), Some(objective), Some(value)) = (
+            score.get("Name").and_then(nbt_str).map(str::to_string),
+            score.get("Objective").and_then(nbt_str).map(str::to_string),
+            score.get("Score").and_then(nbt_number).map(|score| score as i32),
+        ) else { continue };
+        scoreboard.scores.entry(holder).or_default().insert(objective, value);
+    }
+
+    Ok(scoreboard)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScoreDeltas {
+    pub changed: HashMap<String, HashMap<String, i32>>, // holder, objective, new score
+    pub removed: HashMap<String, Vec<String>>, // holder, objectives reset or removed
+}
+
+impl ScoreDeltas {
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// everything that changed, appeared or went away, e.g. with `scoreboard players reset`
+pub fn score_deltas(old: &Scoreboard, new: &Scoreboard) -> ScoreDeltas {
+    let mut deltas = ScoreDeltas::default();
+    let empty = HashMap::new();
+    for (holder, scores) in &new.scores {
+        let old_scores = old.scores.get(holder).unwrap_or(&empty);
+        for (objective, score) in scores {
+            if old_scores.get(objective) != Some(score) {
+                deltas.changed.entry(holder.clone()).or_default().insert(objective.clone(), *score);
+            }
+        }
+    }
+
+    for (holder, scores) in &old.scores {
+        let new_scores = new.scores.get(holder).unwrap_or(&empty);
+        for objective in scores.keys() {
+            if !new_scores.contains_key(objective) {
+                deltas.removed.entry(holder.clone()).or_default().push(objective.clone());
+            }
+        }
+    }
+    deltas
+}
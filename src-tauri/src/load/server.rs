@@ -0,0 +1,200 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::{Path, PathBuf}};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum LayoutMode {
+    #[default]
+    Auto, // server if there's a server.properties next to the path
+    Singleplayer,
+    Server,
+}
+
+/// where each part of a world lives. singleplayer keeps everything in one folder,
+/// paper/spigot split the nether and end into <level-name>_nether and <level-name>_the_end,
+/// all of them under bukkit.yml's world-container if it's set
+#[derive(Debug, Clone, Default)]
+pub struct WorldLayout {
+    pub main: PathBuf, // level.dat, data, datapacks
+    pub icon: PathBuf,
+    pub stats: PathBuf,
+    pub advancements: PathBuf,
+    pub playerdata: PathBuf,
+    pub dimensions: Vec<(String, PathBuf)>, // dimension, folder holding its region dir
+    pub split_worlds: Vec<PathBuf>, // paper's <level-name>_nether and _the_end
+    pub server_root: Option<PathBuf>, // usercache.json lives here
+}
+
+impl WorldLayout {
+    /// `path` is the world folder in singleplayer, the server folder otherwise
+    pub fn resolve(path: &Path, mode: LayoutMode) -> Result<Self> {
+        let is_server = match mode {
+            LayoutMode::Auto => path.join("server.properties").exists(),
+            LayoutMode::Singleplayer => false,
+            LayoutMode::Server => true,
+        };
+
+        if is_server {
+            Self::server(path)
+        } else {
+            Ok(Self::singleplayer(path))
+        }
+    }
+
+    pub fn singleplayer(world_path: &Path) -> Self {
+        let mut layout = WorldLayout {
+            main: world_path.to_path_buf(),
+            icon: world_path.join("icon.png"),
+            stats: world_path.join("stats"),
+            advancements: world_path.join("advancements"),
+            playerdata: world_path.join("playerdata"),
+            dimensions: vec![
+                ("overworld".to_string(), world_path.to_path_buf()),
+                ("the_nether".to_string(), world_path.join("DIM-1")),
+                ("the_end".to_string(), world_path.join("DIM1")),
+            ],
+            split_worlds: Vec::new(),
+            server_root: None,
+        };
+        layout.add_custom_dimensions(world_path);
+        layout
+    }
+
+    pub fn server(server_root: &Path) -> Result<Self> {
+        let properties = read_properties(&server_root.join("server.properties"))?;
+        let level_name = properties.get("level-name").map(String::as_str).unwrap_or("world");
+        let container = world_container(server_root);
+        let main = container.join(level_name);
+        if !main.join("level.dat").exists() {
+            anyhow::bail!("No level.dat in {}", main.display());
+        }
+
+        let mut layout = Self::singleplayer(&main);
+        layout.icon = server_root.join("server-icon.png");
+        layout.server_root = Some(server_root.to_path_buf());
+
+        // paper keeps each dimension in its own world folder, vanilla servers don't
+        for (dimension, suffix, folder) in [("the_nether", "_nether", "DIM-1"), ("the_end", "_the_end", "DIM1")] {
+            let split_world = container.join(format!("{}{}", level_name, suffix));
+            if split_world.join(folder).exists() {
+                if let Some(entry) = layout.dimensions.iter_mut().find(|(id, _)| id == dimension) {
+                    entry.1 = split_world.join(folder);
+                }
+                layout.split_worlds.push(split_world);
+            }
+        }
+
+        println!("[LOAD] Server layout, main world at {}", layout.main.display());
+        Ok(layout)
+    }
+
+    // datapack dimensions: dimensions/<namespace>/<name>
+    fn add_custom_dimensions(&mut self, world_path: &Path) {
+        let custom = world_path.join("dimensions");
+        for namespace in fs::read_dir(&custom).into_iter().flatten().filter_map(|entry| entry.ok()) {
+            for dimension in fs::read_dir(namespace.path()).into_iter().flatten().filter_map(|entry| entry.ok()) {
+                let id = format!("{}:{}", namespace.file_name().to_string_lossy(), dimension.file_name().to_string_lossy());
+                self.dimensions.push((super::strip_mc_prefix(&id).to_string(), dimension.path()));
+            }
+        }
+    }
+
+    pub fn region_dirs(&self) -> Vec<(String, PathBuf)> {
+        self.dimensions.iter()
+            .map(|(dimension, root)| (dimension.clone(), root.join("region")))
+            .filter(|(_, dir)| dir.exists())
+            .collect()
+    }
+
+    /// the main world's datapacks, then any the split dimension worlds carry
+    pub fn datapack_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs = vec![self.main.join("datapacks")];
+        dirs.extend(self.split_worlds.iter()
+            .map(|world| world.join("datapacks"))
+            .filter(|datapacks| datapacks.exists()));
+        dirs
+    }
+
+    /// server folder, or the instance folder holding saves/<world>
+    pub fn usercache(&self) -> Option<PathBuf> {
+        let root = match &self.server_root {
+            Some(root) => root.clone(),
+            None => self.main.parent().filter(|saves| saves.ends_with("saves"))?.parent()?.to_path_buf(),
+        };
+        Some(root.join("usercache.json"))
+    }
+}
+
+/// uuid -> name for everyone who has joined, kept by servers and by the client for lan games
+pub fn read_usercache(path: &Path) -> Result<HashMap<String, String>> {
+    #[derive(Deserialize)]
+    struct Entry {
+        name: String,
+        uuid: String,
+    }
+
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let entries: Vec<Entry> = serde_json::from_str(&content)?;
+    Ok(entries.into_iter().map(|entry| (entry.uuid, entry.name)).collect())
+}
+
+/// bukkit.yml can move every world folder somewhere else, relative to the server or absolute
+fn world_container(server_root: &Path) -> PathBuf {
+    let container = fs::read_to_string(server_root.join("bukkit.yml")).ok()
+        .and_then(|content| read_world_container(&content));
+    match container {
+        Some(container) => server_root.join(container),
+        None => server_root.to_path_buf(),
+    }
+}
+
+/// settings.world-container without pulling in a yaml parser
+fn read_world_container(bukkit_yml: &str) -> Option<String> {
+    let mut in_settings = false;
+    for line in bukkit_yml.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t']) {
+            in_settings = line.trim_end() == "settings:";
+            continue;
+        }
+        let Some(value) = line.trim().strip_prefix("world-container:").filter(|_| in_settings) else { continue };
+        let value = value.trim().trim_matches(['\'', '"']);
+        return (!value.is_empty()).then(|| value.to_string());
+    }
+    None
+}
+
+/// java .properties, good enough for server.properties
+fn read_properties(path: &Path) -> Result<HashMap<String, String>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    Ok(content.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('!'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().replace("\\:", ":").replace("\\\\", "\\")))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_container_comes_from_bukkit_settings() {
+        let bukkit_yml = "settings:\n  allow-end: true\n  world-container: 'worlds'\n  warn-on-overload: true\nspawn-limits:\n  monsters: 70\n";
+        assert_eq!(read_world_container(bukkit_yml).as_deref(), Some("worlds"));
+
+        assert_eq!(read_world_container("settings:\n  allow-end: true\n"), None);
+        assert_eq!(read_world_container("settings:\n  world-container: ''\n"), None);
+        assert_eq!(read_world_container("other:\n  world-container: worlds\n"), None);
+    }
+}
@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use crate::load::{context::*, strip_mc_prefix, Advancement, BaseSubject, Subject, Supplement};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SubjectKind {
+    Item,
+    Entity,
+    Biome,
+    Structure,
+    Effect,
+    Enchantment,
+}
+
+impl SubjectKind {
+    // worldgen tags keep their registry in the tag name
+    fn tag_prefix(&self) -> &'static str {
+        match self {
+            SubjectKind::Biome => "biome/",
+            SubjectKind::Structure => "structure/",
+            _ => "",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectReference {
+    pub advancement: String,
+    pub criterion: String,
+}
+
+/// every advancement criterion that mentions an id. tags were already expanded by extraction
+#[derive(Debug, Default)]
+pub struct SubjectIndex {
+    references: HashMap<SubjectKind, HashMap<String, Vec<SubjectReference>>>,
+    tags: HashMap<String, Vec<String>>, // expanded, for looking up "#id"
+}
+
+impl SubjectIndex {
+    pub fn build(advancements: &HashMap<String, Advancement>, context: &LoadingContext) -> Self {
+        let mut index = SubjectIndex::default();
+
+        let mut keys: Vec<&String> = advancements.keys().collect();
+        keys.sort();
+        for key in keys {
+            for (criterion, subjects) in &advancements[key].requirements {
+                for subject in subjects {
+                    for (kind, id) in subject_ids(subject) {
+                        let references = index.references.entry(kind).or_default().entry(id.to_string()).or_default();
+                        let reference = SubjectReference { advancement: key.clone(), criterion: criterion.clone() };
+                        if !references.contains(&reference) {
+                            references.push(reference);
+                        }
+                    }
+                }
+            }
+        }
+
+        for tag in context.tag_map.keys() {
+            index.tags.insert(tag.clone(), tag_lookup(&context.tag_map, tag, &mut HashSet::new()));
+        }
+
+        println!("[LOAD] Indexed {} subjects", index.references.values().map(|ids| ids.len()).sum::<usize>());
+        index
+    }
+
+    /// "#id" looks up every member of the tag
+    pub fn lookup(&self, kind: SubjectKind, id: &str) -> Vec<&SubjectReference> {
+        let Some(ids) = self.references.get(&kind) else {
+            return Vec::new();
+        };
+
+        let members = match id.strip_prefix('#') {
+            Some(tag) => {
+                let (namespace, path) = tag.split_once(':').unwrap_or(("minecraft", tag));
+                let tag_id = format!("{}:{}{}", namespace, kind.tag_prefix(), path);
+                self.tags.get(&tag_id).cloned().unwrap_or_default()
+            }
+            None => vec![strip_mc_prefix(id).to_string()],
+        };
+
+        let mut references: Vec<&SubjectReference> = Vec::new();
+        for member in &members {
+            for reference in ids.get(member).into_iter().flatten() {
+                if !references.contains(&reference) {
+                    references.push(reference);
+                }
+            }
+        }
+        references
+    }
+
+    /// advancements mentioning the id as any kind of subject
+    pub fn advancements_for(&self, id: &str) -> BTreeSet<&str> {
+        let id = strip_mc_prefix(id);
+        self.references.values()
+            .filter_map(|ids| ids.get(id))
+            .flatten()
+            .map(|reference| reference.advancement.as_str())
+            .collect()
+    }
+}
+
+fn subject_ids(subject: &Subject) -> Vec<(SubjectKind, &str)> {
+    let mut ids: Vec<(SubjectKind, &str)> = match &subject.base {
+        BaseSubject::Item { ids, .. } => ids.iter().map(|id| (SubjectKind::Item, id.as_str())).collect(),
+        BaseSubject::Entity { id, .. } => vec![(SubjectKind::Entity, id.as_str())],
+        BaseSubject::Location { biomes, structures, .. } => biomes.iter().map(|id| (SubjectKind::Biome, id.as_str()))
+            .chain(structures.iter().map(|id| (SubjectKind::Structure, id.as_str())))
+            .collect(),
+        BaseSubject::Effect { id, .. } => vec![(SubjectKind::Effect, id.as_str())],
+        _ => Vec::new(),
+    };
+
+    for supplement in &subject.supplements {
+        match supplement {
+            Supplement::Enchantment { id, .. } => ids.push((SubjectKind::Enchantment, id.as_str())),
+            Supplement::Effect { id, .. } => ids.push((SubjectKind::Effect, id.as_str())),
+            Supplement::Entity { id, .. } => ids.push((SubjectKind::Entity, id.as_str())),
+            Supplement::Biome { id } => ids.push((SubjectKind::Biome, id.as_str())),
+            _ => {}
+        }
+    }
+    ids
+}
@@ -3,7 +3,7 @@ use super::*;
 use anyhow::{Context, Result};
 use flate2::read::GzDecoder;
 use serde::Serialize;
-use std::{collections::HashMap, fs, io::Read, path::Path};
+use std::{collections::HashMap, ffi::OsStr, fs, io::Read, path::Path};
 use crate::structs::*;
 
 #[derive(Debug, Clone, Serialize, Default)]
@@ -15,6 +15,8 @@ pub struct World {
 
     #[serde(skip)]
     pub enabled_datapacks: Vec<String>,
+    #[serde(skip)]
+    pub layout: WorldLayout,
 }
 
 fn read_json(path: &Path) -> Result<serde_json::Value> {
@@ -23,10 +25,10 @@ fn read_json(path: &Path) -> Result<serde_json::Value> {
     serde_json::from_str(&content).with_context(|| format!("Invalid JSON in file {}", path.display()))
 }
 
-pub fn read(world_path: &Path) -> Result<World> {
-    let level_path = world_path.join("level.dat");
-    let mut file = fs::File::open(&level_path)
-        .with_context(|| format!("Failed to open level.dat at {}", level_path.display()))?;
+/// .dat files are gzipped nbt, sometimes uncompressed
+pub fn read_nbt(path: &Path) -> Result<crab_nbt::Nbt> {
+    let mut file = fs::File::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
 
     let mut buffer = Vec::new();
     file.read_to_end(&mut buffer)?;
@@ -41,7 +43,12 @@ pub fn read(world_path: &Path) -> Result<World> {
     };
 
     let mut cursor = std::io::Cursor::new(decompressed_data);
-    let nbt_data = crab_nbt::Nbt::read(&mut cursor)?;
+    crab_nbt::Nbt::read(&mut cursor).with_context(|| format!("Invalid NBT in {}", path.display()))
+}
+
+pub fn read(layout: &WorldLayout) -> Result<World> {
+    let world_path = &layout.main;
+    let nbt_data = read_nbt(&world_path.join("level.dat"))?;
 
     let data = nbt_data
         .root_tag
@@ -116,7 +123,7 @@ pub fn read(world_path: &Path) -> Result<World> {
             })
             .unwrap_or_default();
 
-    let icon_path = crate::cache::cache_world_icon(world_path)?;
+    let icon_path = crate::cache::cache_world_icon(&layout.icon)?;
 
     println!("{:?}", enabled_datapacks);
 
@@ -124,15 +131,96 @@ pub fn read(world_path: &Path) -> Result<World> {
         name,
         version,
         icon_path,
-        enabled_datapacks
+        enabled_datapacks,
+        layout: layout.clone(),
     })
 }
 
-pub fn read_players(world_path: &Path) -> Result<(HashMap<String, Player>, HashMap<String, HashMap<String, AdvancementProgress>>)> {
+/// .dat files in the world's data folder: scoreboard, command storage, raids, random sequences...
+pub fn list_data_files(world_path: &Path) -> Result<Vec<String>> {
+    let data_dir = world_path.join("data");
+    if !data_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files: Vec<String> = fs::read_dir(&data_dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| is_data_file(name))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// a bare file name, nothing that walks out of the data folder.
+/// a drive prefix like `C:evil.dat` would make join replace the folder on windows
+pub fn is_data_file(name: &str) -> bool {
+    name.ends_with(".dat")
+        && !name.contains(['/', '\\', ':'])
+        && !name.starts_with('.')
+        && Path::new(name).file_name() == Some(OsStr::new(name))
+}
+
+/// the whole file as json, for poking at datapack state
+pub fn read_data_file(world_path: &Path, name: &str) -> Result<Option<serde_json::Value>> {
+    let path = world_path.join("data").join(name);
+    if !is_data_file(name) || !path.exists() {
+        return Ok(None);
+    }
+
+    let nbt_data = read_nbt(&path)?;
+    Ok(Some(nbt_to_json(&crab_nbt::NbtTag::Compound(nbt_data.root_tag))))
+}
+
+pub fn nbt_str(tag: &crab_nbt::NbtTag) -> Option<&str> {
+    match tag {
+        crab_nbt::NbtTag::String(s) => Some(s.as_str()),
+        _ => None,
+    }
+}
+
+/// any numeric tag, minecraft isn't consistent about which one it uses
+pub fn nbt_number(tag: &crab_nbt::NbtTag) -> Option<f64> {
+    use crab_nbt::NbtTag;
+    match tag {
+        NbtTag::Byte(v) => Some(*v as f64),
+        NbtTag::Short(v) => Some(*v as f64),
+        NbtTag::Int(v) => Some(*v as f64),
+        NbtTag::Long(v) => Some(*v as f64),
+        NbtTag::Float(v) => Some(*v as f64),
+        NbtTag::Double(v) => Some(*v),
+        _ => None,
+    }
+}
+
+pub fn nbt_to_json(tag: &crab_nbt::NbtTag) -> serde_json::Value {
+    use crab_nbt::NbtTag;
+    use serde_json::{json, Value};
+
+    match tag {
+        NbtTag::End => Value::Null,
+        NbtTag::Byte(v) => json!(v),
+        NbtTag::Short(v) => json!(v),
+        NbtTag::Int(v) => json!(v),
+        NbtTag::Long(v) => json!(v),
+        NbtTag::Float(v) => json!(v),
+        NbtTag::Double(v) => json!(v),
+        NbtTag::ByteArray(bytes) => Value::Array(bytes.iter().map(|b| json!(*b as i8)).collect()),
+        NbtTag::String(s) => Value::String(s.clone()),
+        NbtTag::List(list) => Value::Array(list.iter().map(nbt_to_json).collect()),
+        NbtTag::Compound(compound) => Value::Object(
+            compound.child_tags.iter().map(|(name, child)| (name.clone(), nbt_to_json(child))).collect()
+        ),
+        NbtTag::IntArray(values) => json!(values),
+        NbtTag::LongArray(values) => json!(values),
+    }
+}
+
+pub fn read_players(layout: &WorldLayout) -> Result<(HashMap<String, Player>, HashMap<String, HashMap<String, AdvancementProgress>>)> {
     let mut players = HashMap::new();
     let mut progress: HashMap<String, HashMap<String, AdvancementProgress>> = HashMap::new();
-    let advancements_dir = world_path.join("advancements");
-    let stats_dir = world_path.join("stats");
+    let advancements_dir = &layout.advancements;
+    let stats_dir = &layout.stats;
 
     if !stats_dir.exists() {
         return Ok((players, progress));
@@ -148,13 +236,19 @@ pub fn read_players(world_path: &Path) -> Result<(HashMap<String, Player>, HashM
         // filenames are the uuid
         let uuid = stat_path.file_stem().unwrap().to_str().unwrap().to_string();
         let stats = read_player_stats(&stat_path)?;
+        let playerdata_path = layout.playerdata.join(format!("{}.dat", uuid));
+        let state = read_player_state(&playerdata_path).unwrap_or_else(|e| {
+            println!("[WARN] Failed to read playerdata for {}: {:?}", uuid, e);
+            None
+        });
 
         players.insert(
             uuid.clone(),
             Player {
                 uuid: uuid.clone(),
                 stats,
-                name: None, avatar_url: None,
+                state,
+                ..Default::default()
             },
         );
 
@@ -242,4 +336,22 @@ pub fn read_player_stats(path: &Path) -> Result<PlayerStats> {
         }
     }
     Ok(PlayerStats { stats: stats })
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_file_names_stay_in_the_data_folder() {
+        assert!(is_data_file("scoreboard.dat"));
+        assert!(is_data_file("command_storage_bac.dat"));
+
+        assert!(!is_data_file("../level.dat"));
+        assert!(!is_data_file("..\\level.dat"));
+        assert!(!is_data_file(".hidden.dat"));
+        assert!(!is_data_file("C:evil.dat"));
+        assert!(!is_data_file("C:\\evil.dat"));
+        assert!(!is_data_file("raids.dat_old"));
+    }
+}
@@ -0,0 +1,180 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use crate::load::{archive::*, context::*, strip_mc_prefix};
+
+// chest/archaeology loot table names that don't start with their structure id
+const LOOT_STRUCTURE_ALIASES: [(&str, &str); 4] = [
+    ("bastion", "bastion_remnant"),
+    ("nether_bridge", "fortress"),
+    ("underwater_ruin", "ocean_ruin"),
+    ("woodland_mansion", "mansion"),
+];
+
+const DIMENSION_TAGS: [(&str, &str); 3] = [
+    ("minecraft:biome/is_overworld", "overworld"),
+    ("minecraft:biome/is_nether", "the_nether"),
+    ("minecraft:biome/is_end", "the_end"),
+];
+
+/// where things spawn and generate, from worldgen biomes and structures
+#[derive(Debug, Default)]
+pub struct Worldgen {
+    pub biome_dimensions: HashMap<String, String>,
+    pub structure_biomes: HashMap<String, Vec<String>>,
+    pub entity_biomes: HashMap<String, Vec<String>>, // natural spawners
+    pub entity_structures: HashMap<String, Vec<String>>, // spawn overrides
+    pub item_structures: HashMap<String, Vec<String>>, // chest and archaeology loot
+}
+
+impl Worldgen {
+    pub fn from_archives(archives: &mut [Box<dyn Archive>], context: &LoadingContext) -> Result<Self> {
+        let mut biomes = HashMap::new();
+        let mut structures = HashMap::new();
+        let categories = vec![FileCategory::Biome, FileCategory::Structure];
+
+        // Later archives override earlier ones (datapack behavior)
+        for archive in archives {
+            for file_path in archive.list_files(&categories)? {
+                let Ok(content) = archive.read_file(&file_path) else { continue };
+                let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) else { continue };
+
+                if FileCategory::Biome.matches(&file_path) {
+                    biomes.insert(extract_worldgen_id(&file_path, "biome"), json);
+                } else if FileCategory::Structure.matches(&file_path) {
+                    structures.insert(extract_worldgen_id(&file_path, "structure"), json);
+                }
+            }
+        }
+
+        let mut worldgen = Worldgen::default();
+
+        for (tag, dimension) in DIMENSION_TAGS {
+            for biome in tag_lookup(&context.tag_map, tag, &mut HashSet::new()) {
+                worldgen.biome_dimensions.insert(biome, dimension.to_string());
+            }
+        }
+
+        for (biome, json) in &biomes {
+            for entity in spawner_entities(json.get("spawners")) {
+                push_unique(worldgen.entity_biomes.entry(entity).or_default(), biome);
+            }
+        }
+
+        for (structure, json) in &structures {
+            let structure_biomes = json.get("biomes").map(|value| biome_ids(value, context)).unwrap_or_default();
+            worldgen.structure_biomes.insert(structure.clone(), structure_biomes);
+
+            if let Some(overrides) = json.get("spawn_overrides").and_then(|v| v.as_object()) {
+                for spawn_override in overrides.values() {
+                    for entity in spawn_entries(spawn_override.get("spawns")) {
+                        push_unique(worldgen.entity_structures.entry(entity).or_default(), structure);
+                    }
+                }
+            }
+        }
+
+        let structure_ids: Vec<&String> = structures.keys().collect();
+        for table_id in context.loot_table_map.keys() {
+            let Some(loot_name) = container_loot_name(table_id) else { continue };
+            let matched = loot_table_structures(loot_name, &structure_ids);
+            if matched.is_empty() { continue }
+
+            for item in context.loot_table_items(table_id) {
+                let item_structures = worldgen.item_structures.entry(item).or_default();
+                for structure in &matched {
+                    push_unique(item_structures, structure);
+                }
+            }
+        }
+
+        println!("[LOAD] Loaded worldgen for {} biomes and {} structures", biomes.len(), structures.len());
+        Ok(worldgen)
+    }
+
+    pub fn dimension_of(&self, biome: &str) -> Option<&String> {
+        self.biome_dimensions.get(biome)
+    }
+}
+
+fn push_unique(list: &mut Vec<String>, value: &str) {
+    if !list.iter().any(|existing| existing == value) {
+        list.push(value.to_string());
+    }
+}
+
+/// get biome/structure id from path
+fn extract_worldgen_id(file_path: &str, kind: &str) -> String {
+    // "data/minecraft/worldgen/biome/cherry_grove.json" -> "cherry_grove"
+    // "data/other/worldgen/structure/tower.json" -> "other:tower"
+    let parts: Vec<&str> = file_path.split('/').collect();
+    if parts.len() >= 5 && parts[0] == "data" && parts[2] == "worldgen" && parts[3] == kind {
+        let path = parts[4..].join("/");
+        let path = path.strip_suffix(".json").unwrap_or(&path);
+        strip_mc_prefix(&format!("{}:{}", parts[1], path)).to_string()
+    } else {
+        file_path.strip_suffix(".json").unwrap_or(file_path).to_string()
+    }
+}
+
+/*
+    "spawners": {
+        "monster": [
+            { "type": "minecraft:zombie", "weight": 95, "minCount": 4, "maxCount": 4 },
+*/
+fn spawner_entities(spawners: Option<&serde_json::Value>) -> Vec<String> {
+    let Some(categories) = spawners.and_then(|v| v.as_object()) else {
+        return Vec::new();
+    };
+
+    categories.values().flat_map(|entries| spawn_entries(Some(entries))).collect()
+}
+
+fn spawn_entries(entries: Option<&serde_json::Value>) -> Vec<String> {
+    entries.and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.get("type").and_then(|v| v.as_str()))
+        .map(|entity| strip_mc_prefix(entity).to_string())
+        .collect()
+}
+
+/// structures point at worldgen biome tags, which live under tags/worldgen/biome
+fn biome_ids(value: &serde_json::Value, context: &LoadingContext) -> Vec<String> {
+    let ids: Vec<&str> = match value {
+        serde_json::Value::String(s) => vec![s.as_str()],
+        serde_json::Value::Array(arr) => arr.iter().filter_map(|v| v.as_str()).collect(),
+        _ => Vec::new(),
+    };
+
+    ids.into_iter()
+        .flat_map(|id| match id.strip_prefix('#') {
+            Some(tag) => {
+                let (namespace, path) = tag.split_once(':').unwrap_or(("minecraft", tag));
+                tag_lookup(&context.tag_map, &format!("{}:biome/{}", namespace, path), &mut HashSet::new())
+            }
+            None => vec![strip_mc_prefix(id).to_string()],
+        })
+        .collect()
+}
+
+// "minecraft:chests/village/village_weaponsmith" -> "village/village_weaponsmith"
+fn container_loot_name(table_id: &str) -> Option<&str> {
+    let path = table_id.split_once(':').map_or(table_id, |(_, path)| path);
+    path.strip_prefix("chests/").or_else(|| path.strip_prefix("archaeology/"))
+}
+
+fn loot_table_structures(loot_name: &str, structure_ids: &[&String]) -> Vec<String> {
+    let mut name = loot_name.to_string();
+    let mut base = name.split('/').next().unwrap_or(loot_name).to_string();
+    for (prefix, structure) in LOOT_STRUCTURE_ALIASES {
+        if let Some(rest) = loot_name.strip_prefix(prefix) {
+            name = format!("{}{}", structure, rest);
+            base = structure.to_string();
+        }
+    }
+
+    structure_ids.iter()
+        .filter(|structure| name.starts_with(structure.as_str()) || structure.starts_with(base.as_str()))
+        .map(|structure| structure.to_string())
+        .collect()
+}
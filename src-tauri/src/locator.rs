@@ -0,0 +1,129 @@
+use serde::Serialize;
+use crate::{load::{self, BaseSubject, DimensionLocations}, planner::LocationKind, structs::*, SharedState};
+
+const NETHER_SCALE: f64 = 8.0;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KnownLocation {
+    pub advancement: String,
+    pub criterion: String,
+    pub kind: LocationKind,
+    pub id: String,
+    pub dimension: String,
+    pub x: i32,
+    pub z: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>, // blocks, none when there's no way to compare dimensions
+}
+
+/// scan region files off the async runtime and swap the result in when done
+pub fn spawn_scan(state: SharedState) {
+    tokio::spawn(async move {
+        let layout = state.read().await.data.world.layout.clone();
+        match tokio::task::spawn_blocking(move || load::scan_regions(&layout)).await {
+            Ok(Ok(locations)) => state.write().await.data.locations = locations,
+            Ok(Err(e)) => eprintln!("[ERROR] Failed to scan region files: {}", e),
+            Err(e) => eprintln!("[ERROR] Region scan panicked: {}", e),
+        }
+    });
+}
+
+/// for every unmet location criterion, the closest place we've seen generated
+pub fn nearest_locations(data: &Data, uuid: &str) -> Vec<KnownLocation> {
+    let state = data.players.get(uuid).and_then(|player| player.state.as_ref());
+    let origin = state.map(|state| (state.dimension.as_str(), state.position[0], state.position[2]));
+
+    let mut keys: Vec<&String> = data.advancements.keys().collect();
+    keys.sort();
+
+    let mut found = Vec::new();
+    for key in keys {
+        let advancement = &data.advancements[key];
+        let progress = data.progress.get(key).and_then(|players| players.get(uuid));
+        if data.is_done(key, uuid) {
+            continue;
+        }
+
+        for criterion in advancement.remaining_criteria(progress) {
+            for subject in advancement.requirements.get(&criterion).into_iter().flatten() {
+                let BaseSubject::Location { biomes, structures, dimension, .. } = &subject.base else { continue };
+                let targets = biomes.iter().map(|id| (LocationKind::Biome, id))
+                    .chain(structures.iter().map(|id| (LocationKind::Structure, id)));
+
+                for (kind, id) in targets {
+                    let Some((location_dimension, x, z, distance)) = nearest(data, kind, id, dimension.as_deref(), origin) else { continue };
+                    found.push(KnownLocation {
+                        advancement: key.clone(),
+                        criterion: criterion.clone(),
+                        kind,
+                        id: id.clone(),
+                        dimension: location_dimension,
+                        x, z, distance,
+                    });
+                }
+            }
+        }
+    }
+    found
+}
+
+fn nearest(
+    data: &Data,
+    kind: LocationKind,
+    id: &str,
+    dimension: Option<&str>,
+    origin: Option<(&str, f64, f64)>
+) -> Option<(String, i32, i32, Option<f64>)> {
+    let mut best: Option<(String, i32, i32, Option<f64>)> = None;
+
+    for (location_dimension, locations) in &data.locations.dimensions {
+        if dimension.is_some_and(|dimension| dimension != location_dimension) {
+            continue;
+        }
+        for [x, z] in positions(locations, kind, id) {
+            let distance = origin.and_then(|origin| distance(origin, location_dimension, *x, *z));
+            let better = match &best {
+                None => true,
+                Some((_, _, _, best_distance)) => match (distance, best_distance) {
+                    (Some(distance), Some(best_distance)) => distance < *best_distance,
+                    (Some(_), None) => true,
+                    _ => false,
+                },
+            };
+            if better {
+                best = Some((location_dimension.clone(), *x, *z, distance));
+            }
+        }
+    }
+    best
+}
+
+fn positions<'a>(locations: &'a DimensionLocations, kind: LocationKind, id: &str) -> &'a [[i32; 2]] {
+    let positions = match kind {
+        LocationKind::Biome => locations.biomes.get(id),
+        LocationKind::Structure => locations.structures.get(id),
+        LocationKind::Dimension => None,
+    };
+    positions.map_or(&[], |positions| positions.as_slice())
+}
+
+/// overworld and nether compare through portal scaling, anything else only within itself
+fn distance((dimension, x, z): (&str, f64, f64), target_dimension: &str, target_x: i32, target_z: i32) -> Option<f64> {
+    let scale = |dimension: &str| match dimension {
+        "overworld" => Some(1.0),
+        "the_nether" => Some(NETHER_SCALE),
+        _ => None,
+    };
+
+    let (from_scale, to_scale) = if dimension == target_dimension {
+        (1.0, 1.0)
+    } else {
+        (scale(dimension)?, scale(target_dimension)?)
+    };
+
+    // measured in the target dimension's blocks
+    let dx = x * from_scale / to_scale - target_x as f64;
+    let dz = z * from_scale / to_scale - target_z as f64;
+    Some((dx * dx + dz * dz).sqrt())
+}
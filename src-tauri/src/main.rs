@@ -5,69 +5,141 @@ mod load;
 mod events;
 mod cache;
 mod outbound;
+mod config;
+mod spoilers;
+mod recommend;
+mod planner;
+mod timeline;
+mod history;
+mod search;
+mod teams;
+mod scoring;
+mod stats;
+mod hints;
+mod skins;
+mod locator;
+mod sync;
+mod api;
 
 use bytes;
 use structs::Data;
 use anyhow::Result;
 use futures::Stream;
 use reqwest::header;
-use sha2::{Sha256, Digest};
-use tower_http::services::ServeDir;
-use tokio::sync::{broadcast,RwLock};
-use std::{collections::{HashSet}, sync::Arc};
-use axum::{extract::State, http::{HeaderMap, StatusCode}, response::{sse::{Event, Sse}, IntoResponse}, routing::get, Router};
+use tower_http::{compression::CompressionLayer, services::ServeDir};
+use tokio::sync::{broadcast, RwLock};
+use std::{collections::{HashSet}, sync::{Arc, Mutex}};
+use serde::Deserialize;
+use axum::{extract::{Query, State}, http::{HeaderMap, StatusCode}, response::{sse::{Event, Sse}, IntoResponse}, routing::{get, post, put}, Router};
 
-use crate::cache::Cache;
+use crate::{cache::Cache, config::Config, history::History};
 
 const SERVER_ADDR: &str = "127.0.0.1:3000";
 
 pub struct AppState {
     data: Data,
-    etag: String, // hash for the data
-    data_bytes: bytes::Bytes, // skip that expensive cloning
-    
-    update_tx: broadcast::Sender<events::UpdateEvent>,
-    processing_uuids: HashSet<String>,
+    definitions: sync::Payload, // static, hashed once
+    progress_sync: sync::ProgressSync,
+    init_cache: Mutex<Option<(u64, bytes::Bytes)>>, // full /api/init body, built on request
+
+    events: events::EventLog,
+    processing: HashSet<String>, // uuids and data files being re-read
+    config: Config,
+    history: History,
     _cache: Cache,
 }
 type SharedState = Arc<RwLock<AppState>>;
 
-fn build_response_bytes(data: &Data) -> (String, bytes::Bytes) {
-    let data_bytes = bytes::Bytes::from(serde_json::to_vec(&data).unwrap());
-    let mut hasher = Sha256::new();
-    hasher.update(&data_bytes);
-    let etag = hex::encode(hasher.finalize());
+impl AppState {
+    fn init_etag(&self) -> String {
+        format!("{}-{}", self.definitions.etag, self.progress_sync.revision())
+    }
 
-    (etag, data_bytes)
+    /// everything, re-serialized at most once per progress revision
+    fn init_bytes(&self) -> bytes::Bytes {
+        let revision = self.progress_sync.revision();
+        let mut cache = self.init_cache.lock().unwrap();
+        match &*cache {
+            Some((cached_revision, bytes)) if *cached_revision == revision => bytes.clone(),
+            _ => {
+                let bytes = bytes::Bytes::from(serde_json::to_vec(&self.data).unwrap());
+                *cache = Some((revision, bytes.clone()));
+                bytes
+            }
+        }
+    }
 }
 
 async fn axum_server() -> Result<()> {
     let cache = Cache::new().await?;
-    let (update_tx, _) = broadcast::channel(32);
+    let config = Config::load()?;
 
     println!("Loading initial world data...");
-    let data = load::load().await?;
+    let mut data = load::load(&config).await?;
+    for team in &config.teams {
+        teams::apply(&mut data, team);
+    }
+
+    let mut history = History::open()?;
+    history.record_snapshot(&data)?;
+
+    let definitions_payload = sync::definitions_payload(&data);
+    let progress_sync = sync::ProgressSync::new(&data);
 
-    let (etag, data_bytes) = build_response_bytes(&data);
-    
     let state = Arc::new(RwLock::new(AppState {
-        data, data_bytes, etag,
-        update_tx: update_tx.clone(),
-        processing_uuids: HashSet::new(),
+        data,
+        definitions: definitions_payload,
+        progress_sync,
+        init_cache: Mutex::new(None),
+        events: events::EventLog::new(),
+        processing: HashSet::new(),
+        config,
+        history,
         _cache: cache,
     }));
 
     
 
+    println!("Scanning region files");
+    locator::spawn_scan(state.clone());
+
     println!("Starting file watcher");
-    events::file_watcher(state.clone())?;
+    let layout = state.read().await.data.world.layout.clone();
+    events::file_watcher(state.clone(), layout)?;
 
     println!("Starting web server");
     let app = Router::new()
         .route("/api/init", get(init))
         .route("/api/events", get(event))
+        .route("/api/definitions", get(definitions))
+        .route("/api/progress", get(progress))
+        .route("/api/advancements/{id}", get(api::advancement))
+        .route("/api/advancements/{id}/progress", get(api::advancement_progress))
+        .route("/api/categories/{key}", get(api::category))
+        .route("/api/players", get(api::players))
+        .route("/api/players/{uuid}", get(api::player))
+        .route("/api/players/{uuid}/progress", get(api::player_progress))
+        .route("/api/players/{uuid}/stats", get(api::player_stat_progress))
+        .route("/api/players/{uuid}/hints", get(api::item_hints))
+        .route("/api/players/{uuid}/locations", get(api::nearest_locations))
+        .route("/api/locations/rescan", post(api::rescan_locations))
+        .route("/api/players/{uuid}/objectives", get(api::player_objective_progress))
+        .route("/api/scoreboard", get(api::scoreboard))
+        .route("/api/world/data", get(api::world_data_files))
+        .route("/api/world/data/{file}", get(api::world_data_file))
+        .route("/api/players/{uuid}/next", get(api::next_advancements))
+        .route("/api/players/{uuid}/expedition", get(api::expedition))
+        .route("/api/players/{uuid}/timeline", get(api::player_timeline))
+        .route("/api/players/{uuid}/history", get(api::player_history))
+        .route("/api/history/daily", get(api::daily_completions))
+        .route("/api/search", get(api::search))
+        .route("/api/leaderboard", get(api::leaderboard))
+        .route("/api/teams", get(api::teams))
+        .route("/api/teams/{name}", put(api::put_team).delete(api::delete_team))
+        .route("/api/subjects/{kind}/{id}", get(api::subject_usages))
         .nest_service(format!("/{}", cache::CACHE_URL).as_str(), ServeDir::new(cache::CACHE_DIR))
         // .fallback(embed::static_handler) 
+        .layer(CompressionLayer::new()) // gzip/br by accept-encoding, skips images and sse
         .with_state(state);
 
     let listener = tokio::net::TcpListener::bind(SERVER_ADDR).await?;
@@ -77,29 +149,151 @@ async fn axum_server() -> Result<()> {
 }
 
 // --- API Handlers ---
-async fn init(State(state): State<SharedState>, headers: HeaderMap) -> impl IntoResponse {
+#[derive(Deserialize)]
+struct InitQuery {
+    player: Option<String>, // selected player, for spoiler mode
+}
+
+async fn init(State(state): State<SharedState>, Query(query): Query<InitQuery>, headers: HeaderMap) -> impl IntoResponse {
     let etag = headers.get(header::IF_NONE_MATCH).and_then(|value| value.to_str().ok());
     let app = state.read().await;
 
-    if etag == Some(app.etag.as_str()) {
+    // redacted payloads depend on the player, so they get their own etag
+    let spoiler_player = query.player.filter(|_| app.config.spoiler_mode);
+    let current_etag = match &spoiler_player {
+        Some(uuid) => format!("{}-{}", app.init_etag(), uuid),
+        None => app.init_etag(),
+    };
+
+    if etag == Some(current_etag.as_str()) {
         return StatusCode::NOT_MODIFIED.into_response();
     }
 
+    let body = match &spoiler_player {
+        Some(uuid) => spoilers::redacted_response_bytes(&app.data, uuid),
+        None => app.init_bytes(),
+    };
+
     (StatusCode::OK, [
         (header::CONTENT_TYPE, "application/json"),
         (header::CACHE_CONTROL, "public, max-age=0, must-revalidate"),
-        (header::ETAG, &app.etag.clone()),
-    ], app.data_bytes.clone()).into_response()
+        (header::ETAG, current_etag.as_str()),
+    ], body).into_response()
 }
 
-async fn event(State(state): State<SharedState>) -> Sse<impl Stream<Item = Result<Event, std::convert::Infallible>>> {
-    let mut rx = state.read().await.update_tx.subscribe();
+async fn definitions(State(state): State<SharedState>, Query(query): Query<InitQuery>, headers: HeaderMap) -> impl IntoResponse {
+    let etag = headers.get(header::IF_NONE_MATCH).and_then(|value| value.to_str().ok());
+    let app = state.read().await;
+
+    // same redaction as /api/init, hashed per player instead of once
+    let redacted = query.player
+        .filter(|_| app.config.spoiler_mode)
+        .map(|uuid| sync::redacted_definitions_payload(&app.data, &uuid));
+    let payload = redacted.as_ref().unwrap_or(&app.definitions);
+
+    if etag == Some(payload.etag.as_str()) {
+        return StatusCode::NOT_MODIFIED.into_response();
+    }
+
+    (StatusCode::OK, [
+        (header::CONTENT_TYPE, "application/json"),
+        (header::CACHE_CONTROL, "public, max-age=0, must-revalidate"),
+        (header::ETAG, payload.etag.as_str()),
+    ], payload.bytes.clone()).into_response()
+}
+
+#[derive(Deserialize)]
+struct ProgressQuery {
+    since: Option<u64>, // revision the client already has
+}
+
+async fn progress(State(state): State<SharedState>, Query(query): Query<ProgressQuery>, headers: HeaderMap) -> impl IntoResponse {
+    let etag = headers.get(header::IF_NONE_MATCH).and_then(|value| value.to_str().ok());
+    let app = state.read().await;
+
+    let current_etag = format!("progress-{}-{}", app.progress_sync.revision(), query.since.unwrap_or(0));
+    if etag == Some(current_etag.as_str()) {
+        return StatusCode::NOT_MODIFIED.into_response();
+    }
+
+    (StatusCode::OK, [
+        (header::CONTENT_TYPE, "application/json"),
+        (header::CACHE_CONTROL, "public, max-age=0, must-revalidate"),
+        (header::ETAG, current_etag.as_str()),
+    ], app.progress_sync.delta(query.since)).into_response()
+}
+
+fn sequenced_event(sequenced: &events::SequencedEvent) -> Option<Event> {
+    let json = serde_json::to_string(&sequenced.event).ok()?;
+    Some(Event::default().id(sequenced.id.to_string()).data(json))
+}
+
+fn resync_event() -> Event {
+    // the client missed too much, it should re-fetch /api/init
+    Event::default().event("resync").data("{}")
+}
+
+async fn event(State(state): State<SharedState>, headers: HeaderMap) -> Sse<impl Stream<Item = Result<Event, std::convert::Infallible>>> {
+    let last_event_id = headers.get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+
+    // subscribe and read the replay buffer under the same lock so nothing slips between them
+    let (mut rx, replay, head) = {
+        let app = state.read().await;
+        let replay = last_event_id.map(|last_id| app.events.since(last_id));
+        (app.events.subscribe(), replay, app.events.last_id())
+    };
+
     let stream = async_stream::stream! {
+        let mut last_sent = last_event_id.unwrap_or(head);
+        match replay {
+            Some(Some(missed)) => {
+                for sequenced in missed {
+                    if let Some(event) = sequenced_event(&sequenced) {
+                        yield Ok(event);
+                    }
+                    last_sent = sequenced.id;
+                }
+            }
+            Some(None) => {
+                last_sent = head;
+                yield Ok(resync_event());
+            }
+            None => {}
+        }
+
         loop {
-            if let Ok(update) = rx.recv().await {
-                if let Ok(json) = serde_json::to_string(&update) {
-                    yield Ok(Event::default().data(json));
+            match rx.recv().await {
+                Ok(sequenced) => {
+                    if sequenced.id <= last_sent { continue } // already replayed
+                    if let Some(event) = sequenced_event(&sequenced) {
+                        yield Ok(event);
+                    }
+                    last_sent = sequenced.id;
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    // the channel dropped some, the replay buffer usually still has them
+                    let (missed, head) = {
+                        let app = state.read().await;
+                        (app.events.since(last_sent), app.events.last_id())
+                    };
+                    match missed {
+                        Some(missed) => {
+                            for sequenced in missed {
+                                if let Some(event) = sequenced_event(&sequenced) {
+                                    yield Ok(event);
+                                }
+                                last_sent = sequenced.id;
+                            }
+                        }
+                        None => {
+                            last_sent = head;
+                            yield Ok(resync_event());
+                        }
+                    }
                 }
+                Err(broadcast::error::RecvError::Closed) => break,
             }
         }
     };
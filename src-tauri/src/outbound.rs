@@ -7,9 +7,8 @@ use anyhow::Context;
 
 /*
 Attributions
-mc-heads.net
-Iso heads https://mc-heads.net/head/<texture_id>/32
-Faces https://mc-heads.net/avatar/<uuid>
+mojang
+skins https://textures.minecraft.net/texture/<texture_id>, faces and iso heads are rendered from these in skins.rs
 
 minecraft.wiki
 biomes https://minecraft.wiki/images/BiomeSprite_<biome>.png
@@ -26,8 +25,13 @@ fn username_api_url(uuid: &str) -> String {
 }
 
 #[inline]
-fn face_api_url(uuid: &str) -> String {
-    format!("https://mc-heads.net/avatar/{}/8", uuid)
+fn session_profile_url(uuid: &str) -> String {
+    format!("https://sessionserver.mojang.com/session/minecraft/profile/{}", uuid)
+}
+
+#[inline]
+fn skin_url(texture_id: &str) -> String {
+    format!("https://textures.minecraft.net/texture/{}", texture_id)
 }
 
 fn client() -> reqwest::Client {
@@ -52,17 +56,40 @@ pub async fn fetch_username(uuid: &str) -> Result<String> {
     Ok(profile.name)
 }
 
-pub async fn fetch_user_face(uuid: &str) -> Result<Vec<u8>> {
-    let response = client().get(&face_api_url(uuid)).send().await
-        .context("Failed to send request to face API")?;
+/// none if the player uses a default skin
+pub async fn fetch_skin_texture_id(uuid: &str) -> Result<Option<String>> {
+    #[derive(Deserialize)]
+    struct Property {
+        name: String,
+        value: String,
+    }
+    #[derive(Deserialize)]
+    struct SessionProfile {
+        properties: Vec<Property>,
+    }
+
+    let profile = client().get(session_profile_url(uuid)).send().await
+        .context("Failed to send request to session server")?
+        .json::<SessionProfile>().await
+        .context("Failed to parse JSON")?;
+
+    match profile.properties.iter().find(|property| property.name == "textures") {
+        Some(textures) => crate::skins::skin_texture_id(&textures.value),
+        None => Ok(None),
+    }
+}
+
+pub async fn fetch_skin(texture_id: &str) -> Result<Vec<u8>> {
+    let response = client().get(skin_url(texture_id)).send().await
+        .context("Failed to send request for skin")?;
 
     if !response.status().is_success() {
-        return Err(anyhow!("Face API returned error status: {}", response.status()));
+        return Err(anyhow!("Skin request returned error status: {}", response.status()));
     }
-    
-    let avatar_bytes = response.bytes().await
-        .context("Failed to read avatar image bytes")?
+
+    let skin_bytes = response.bytes().await
+        .context("Failed to read skin image bytes")?
         .to_vec();
-    
-    Ok(avatar_bytes)
+
+    Ok(skin_bytes)
 }
\ No newline at end of file
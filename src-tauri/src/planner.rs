@@ -0,0 +1,144 @@
+use serde::Serialize;
+use std::{cmp::Reverse, collections::{BTreeMap, HashMap}};
+use crate::{load::{Advancement, BaseSubject, Subject, Worldgen}, structs::*};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LocationKind {
+    Biome,
+    Structure,
+    Dimension,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum PlanReason {
+    Location,
+    Entity { id: String }, // spawns there
+    Item { id: String }, // generates in loot there
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedCriterion {
+    pub advancement: String,
+    pub criterion: String,
+    pub reason: PlanReason,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationBucket {
+    pub kind: LocationKind,
+    pub id: String,
+    pub criteria: Vec<PlannedCriterion>,
+}
+
+type Buckets = BTreeMap<(LocationKind, String), Vec<PlannedCriterion>>;
+
+fn add(buckets: &mut Buckets, kind: LocationKind, id: &str, planned: &PlannedCriterion) {
+    let bucket = buckets.entry((kind, id.to_string())).or_default();
+    let duplicate = bucket.iter().any(|existing| existing.advancement == planned.advancement && existing.criterion == planned.criterion);
+    if !duplicate {
+        bucket.push(planned.clone());
+    }
+}
+
+fn add_biome(buckets: &mut Buckets, worldgen: &Worldgen, biome: &str, planned: &PlannedCriterion) {
+    add(buckets, LocationKind::Biome, biome, planned);
+    if let Some(dimension) = worldgen.dimension_of(biome) {
+        add(buckets, LocationKind::Dimension, dimension, planned);
+    }
+}
+
+fn add_structure(buckets: &mut Buckets, worldgen: &Worldgen, structure: &str, planned: &PlannedCriterion) {
+    add(buckets, LocationKind::Structure, structure, planned);
+    // a structure's dimension follows from the biomes it generates in
+    let dimension = worldgen.structure_biomes.get(structure)
+        .and_then(|biomes| biomes.iter().find_map(|biome| worldgen.dimension_of(biome)));
+    if let Some(dimension) = dimension {
+        add(buckets, LocationKind::Dimension, dimension, planned);
+    }
+}
+
+fn bucket_subject(buckets: &mut Buckets, worldgen: &Worldgen, subject: &Subject, advancement: &str, criterion: &str) {
+    let make = |reason| PlannedCriterion { advancement: advancement.to_string(), criterion: criterion.to_string(), reason };
+
+    match &subject.base {
+        BaseSubject::Location { biomes, structures, dimension, .. } => {
+            let planned = make(PlanReason::Location);
+            for biome in biomes {
+                add_biome(buckets, worldgen, biome, &planned);
+            }
+            for structure in structures {
+                add_structure(buckets, worldgen, structure, &planned);
+            }
+            if let Some(dimension) = dimension {
+                add(buckets, LocationKind::Dimension, dimension, &planned);
+            }
+        }
+        BaseSubject::Entity { id, .. } => {
+            let planned = make(PlanReason::Entity { id: id.clone() });
+            for biome in worldgen.entity_biomes.get(id).into_iter().flatten() {
+                add_biome(buckets, worldgen, biome, &planned);
+            }
+            for structure in worldgen.entity_structures.get(id).into_iter().flatten() {
+                add_structure(buckets, worldgen, structure, &planned);
+            }
+        }
+        BaseSubject::Item { ids, .. } => {
+            for id in ids {
+                let planned = make(PlanReason::Item { id: id.clone() });
+                for structure in worldgen.item_structures.get(id).into_iter().flatten() {
+                    add_structure(buckets, worldgen, structure, &planned);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn plan_advancement(buckets: &mut Buckets, worldgen: &Worldgen, advancement: &Advancement, progress: Option<&AdvancementProgress>) {
+    for criterion in advancement.remaining_criteria(progress) {
+        let subjects = advancement.requirements.get(&criterion).into_iter().flatten()
+            .chain(advancement.common_subjects.iter().flatten());
+        for subject in subjects {
+            bucket_subject(buckets, worldgen, subject, &advancement.key, &criterion);
+        }
+    }
+}
+
+/// outstanding criteria bucketed by where they can be done, biggest buckets first.
+/// `concealed` advancements are left out entirely, for spoiler mode
+pub fn plan(
+    data: &Data,
+    progress: &HashMap<String, AdvancementProgress>,
+    kind: Option<LocationKind>,
+    id: Option<&str>,
+    concealed: impl Fn(&str) -> bool
+) -> Vec<LocationBucket> {
+    let mut buckets = Buckets::new();
+
+    // sorted so criteria come out in the same order within each bucket
+    let mut keys: Vec<&String> = data.advancements.keys().collect();
+    keys.sort();
+
+    for key in keys {
+        let advancement = &data.advancements[key];
+        let advancement_progress = progress.get(key);
+        if advancement_progress.is_some_and(|p| p.done) || concealed(key) {
+            continue;
+        }
+        plan_advancement(&mut buckets, &data.worldgen, advancement, advancement_progress);
+    }
+
+    let mut result: Vec<LocationBucket> = buckets.into_iter()
+        .filter(|((bucket_kind, bucket_id), _)| {
+            kind.map_or(true, |k| k == *bucket_kind) && id.map_or(true, |i| i == bucket_id.as_str())
+        })
+        .map(|((kind, id), criteria)| LocationBucket { kind, id, criteria })
+        .collect();
+
+    result.sort_by_key(|bucket| Reverse(bucket.criteria.len()));
+    result
+}
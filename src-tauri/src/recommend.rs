@@ -0,0 +1,99 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use crate::{load::{Advancement, BaseSubject}, structs::*};
+
+pub const DEFAULT_LIMIT: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recommendation {
+    pub key: String,
+    pub score: f32,
+    pub remaining_criteria: Vec<String>,
+    pub remaining_groups: usize,
+    // remaining criteria that share a biome/structure/dimension with another remaining criterion
+    pub clustered_criteria: usize,
+}
+
+fn frame_weight(advancement_type: &AdvancementType) -> f32 {
+    match advancement_type {
+        AdvancementType::Root | AdvancementType::Task => 1.0,
+        AdvancementType::Goal => 0.8,
+        AdvancementType::Challenge => 0.6,
+    }
+}
+
+// spreadsheet classes, incidental ones tend to happen on their own
+fn class_weight(class: &str) -> f32 {
+    match class {
+        "Incidental" => 1.3,
+        "Subset" | "Child" => 1.1,
+        "Super Set" => 0.8,
+        _ => 1.0,
+    }
+}
+
+// the spreadsheet time column is free text, take the leading number as minutes
+fn time_penalty(time: Option<&str>) -> f32 {
+    let Some(time) = time else { return 0.0 };
+    let number: String = time.trim().chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect();
+    number.parse::<f32>().map_or(0.0, |minutes| (minutes / 60.0).min(5.0))
+}
+
+fn location_keys(advancement: &Advancement, criterion: &str) -> HashSet<String> {
+    let mut keys = HashSet::new();
+    for subject in advancement.requirements.get(criterion).into_iter().flatten() {
+        if let BaseSubject::Location { biomes, structures, dimension, .. } = &subject.base {
+            keys.extend(biomes.iter().map(|b| format!("biome:{}", b)));
+            keys.extend(structures.iter().map(|s| format!("structure:{}", s)));
+            keys.extend(dimension.iter().map(|d| format!("dimension:{}", d)));
+        }
+    }
+    keys
+}
+
+fn clustered_criteria(advancement: &Advancement, remaining: &[String]) -> usize {
+    let keys: Vec<HashSet<String>> = remaining.iter().map(|c| location_keys(advancement, c)).collect();
+    let mut counts: HashMap<&String, usize> = HashMap::new();
+    for key in keys.iter().flatten() {
+        *counts.entry(key).or_default() += 1;
+    }
+    keys.iter()
+        .filter(|criterion_keys| criterion_keys.iter().any(|key| counts[key] > 1))
+        .count()
+}
+
+/// incomplete advancements whose parent is done, best first. callers cut it down to a limit
+pub fn recommend(data: &Data, uuid: &str) -> Vec<Recommendation> {
+    let mut recommendations: Vec<Recommendation> = data.advancements.values()
+        .filter(|advancement| !data.is_done(&advancement.key, uuid))
+        .filter(|advancement| advancement.parent.as_deref().map_or(true, |parent| data.is_done(parent, uuid)))
+        .map(|advancement| {
+            let progress = data.progress.get(&advancement.key).and_then(|players| players.get(uuid));
+            let remaining_groups = advancement.unmet_groups(progress).len();
+            let remaining_criteria = advancement.remaining_criteria(progress);
+            let clustered = clustered_criteria(advancement, &remaining_criteria);
+
+            let mut score = 100.0 / (1.0 + remaining_groups as f32);
+            score *= frame_weight(&advancement.advancement_type);
+            score *= class_weight(&advancement.spreadsheet_info.class);
+            score += clustered as f32 * 2.0;
+            score -= time_penalty(advancement.spreadsheet_info.time.as_deref()) * 10.0;
+            // we can't tell what's left for these
+            if advancement.command_driven {
+                score *= 0.5;
+            }
+
+            Recommendation {
+                key: advancement.key.clone(),
+                score,
+                remaining_criteria,
+                remaining_groups,
+                clustered_criteria: clustered,
+            }
+        })
+        .collect();
+
+    recommendations.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.key.cmp(&b.key)));
+    recommendations
+}
@@ -0,0 +1,154 @@
+use chrono::{Days, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use crate::{load::Advancement, structs::*, teams::is_team};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScoringConfig {
+    pub task: f64,
+    pub goal: f64,
+    pub challenge: f64,
+    pub classes: HashMap<String, f64>, // spreadsheet class multipliers, 1 if missing
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        ScoringConfig { task: 1.0, goal: 3.0, challenge: 5.0, classes: HashMap::new() }
+    }
+}
+
+impl ScoringConfig {
+    pub fn points(&self, advancement: &Advancement) -> f64 {
+        let frame = match advancement.advancement_type {
+            AdvancementType::Root => 0.0,
+            AdvancementType::Task => self.task,
+            AdvancementType::Goal => self.goal,
+            AdvancementType::Challenge => self.challenge,
+        };
+        frame * self.classes.get(&advancement.spreadsheet_info.class).copied().unwrap_or(1.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerScore {
+    pub uuid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub score: f64,
+    pub completed: usize,
+    pub streak: usize, // days in a row up to today or yesterday
+    pub longest_streak: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryScore {
+    pub uuid: String,
+    pub score: f64,
+    pub completed: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirstCompletion {
+    pub uuid: String,
+    pub completed_at: Timestamp,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Leaderboard {
+    pub players: Vec<PlayerScore>,
+    pub categories: BTreeMap<String, Vec<CategoryScore>>,
+    pub first_completions: BTreeMap<String, FirstCompletion>,
+}
+
+/// scores for actual players, teams are co-op and don't compete
+pub fn leaderboard(data: &Data, config: &ScoringConfig) -> Leaderboard {
+    let mut categories: BTreeMap<String, HashMap<String, CategoryScore>> = BTreeMap::new();
+    let mut first_completions: BTreeMap<String, FirstCompletion> = BTreeMap::new();
+
+    for (key, players) in &data.progress {
+        let Some(advancement) = data.advancements.get(key) else { continue };
+        let points = config.points(advancement);
+
+        for (uuid, progress) in players.iter().filter(|(uuid, progress)| progress.done && !is_team(uuid)) {
+            let category = categories.entry(advancement.category.clone()).or_default()
+                .entry(uuid.clone())
+                .or_insert_with(|| CategoryScore { uuid: uuid.clone(), score: 0.0, completed: 0 });
+            category.score += points;
+            category.completed += 1;
+
+            let Some(completed_at) = progress.completed_at else { continue };
+            let is_first = first_completions.get(key).map_or(true, |first| {
+                completed_at < first.completed_at || (completed_at == first.completed_at && *uuid < first.uuid)
+            });
+            if is_first {
+                first_completions.insert(key.clone(), FirstCompletion { uuid: uuid.clone(), completed_at });
+            }
+        }
+    }
+
+    let mut players: Vec<PlayerScore> = data.players.keys()
+        .filter(|uuid| !is_team(uuid))
+        .map(|uuid| player_score(data, config, uuid))
+        .collect();
+    players.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.uuid.cmp(&b.uuid)));
+
+    let categories = categories.into_iter()
+        .map(|(category, scores)| {
+            let mut scores: Vec<CategoryScore> = scores.into_values().collect();
+            scores.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.uuid.cmp(&b.uuid)));
+            (category, scores)
+        })
+        .collect();
+
+    Leaderboard { players, categories, first_completions }
+}
+
+pub fn player_score(data: &Data, config: &ScoringConfig, uuid: &str) -> PlayerScore {
+    let mut score = 0.0;
+    let mut completed = 0;
+    let mut days = BTreeSet::new();
+
+    for (key, players) in &data.progress {
+        let Some(progress) = players.get(uuid).filter(|progress| progress.done) else { continue };
+        let Some(advancement) = data.advancements.get(key) else { continue };
+        score += config.points(advancement);
+        completed += 1;
+        if let Some(completed_at) = progress.completed_at {
+            days.insert(completed_at.date_naive());
+        }
+    }
+
+    let (streak, longest_streak) = streaks(&days, Local::now().date_naive());
+    PlayerScore {
+        uuid: uuid.to_string(),
+        name: data.players.get(uuid).and_then(|player| player.name.clone()),
+        score, completed, streak, longest_streak,
+    }
+}
+
+/// (current, longest) runs of consecutive days with a completion
+fn streaks(days: &BTreeSet<NaiveDate>, today: NaiveDate) -> (usize, usize) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for day in days {
+        run = match previous {
+            Some(previous) if previous.checked_add_days(Days::new(1)) == Some(*day) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(*day);
+    }
+
+    let yesterday = today.checked_sub_days(Days::new(1));
+    let current = match previous {
+        Some(last) if last == today || Some(last) == yesterday => run,
+        _ => 0,
+    };
+    (current, longest)
+}
@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use crate::{load::{Advancement, SubjectKind}, structs::*};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    pub q: Option<String>, // words in the name or description
+    pub category: Option<String>,
+    pub class: Option<String>,
+    pub frame: Option<AdvancementType>,
+    pub namespace: Option<String>,
+    pub subject: Option<String>, // any subject id, see SubjectIndex
+    pub player: Option<String>,
+    pub done: Option<bool>, // needs a player
+    pub limit: Option<usize>, // applied by the caller, after spoiler filtering
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub key: String,
+    pub display_name: String,
+    pub category: String,
+    pub advancement_type: AdvancementType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub done: Option<bool>,
+    pub score: usize,
+}
+
+pub fn search(data: &Data, query: &SearchQuery) -> Vec<SearchHit> {
+    let terms: Vec<String> = query.q.as_deref().unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect();
+    let subject_matches = query.subject.as_deref()
+        .map(|subject| data.subjects.advancements_for(subject));
+    let player_progress = query.player.as_deref().map(|uuid| data.player_progress(uuid));
+
+    let mut hits: Vec<SearchHit> = data.advancements.values()
+        .filter(|advancement| !data.categories.contains_key(&advancement.key)) // roots are just headers
+        .filter(|advancement| query.category.as_ref().map_or(true, |category| &advancement.category == category))
+        .filter(|advancement| query.class.as_ref().map_or(true, |class| advancement.spreadsheet_info.class.eq_ignore_ascii_case(class)))
+        .filter(|advancement| query.frame.as_ref().map_or(true, |frame| &advancement.advancement_type == frame))
+        .filter(|advancement| query.namespace.as_ref().map_or(true, |namespace| &advancement.source == namespace))
+        .filter(|advancement| match &subject_matches {
+            Some(keys) => keys.contains(advancement.key.as_str()),
+            None => true,
+        })
+        .filter_map(|advancement| {
+            let score = text_score(advancement, &terms)?;
+            let done = player_progress.as_ref()
+                .map(|progress| progress.get(&advancement.key).is_some_and(|p| p.done));
+            if query.done.is_some() && done != query.done {
+                return None;
+            }
+
+            Some(SearchHit {
+                key: advancement.key.clone(),
+                display_name: advancement.display_name.clone(),
+                category: advancement.category.clone(),
+                advancement_type: advancement.advancement_type.clone(),
+                done,
+                score,
+            })
+        })
+        .collect();
+
+    hits.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.key.cmp(&b.key)));
+    hits
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubjectUsage {
+    pub advancement: String,
+    pub criterion: String,
+    pub display_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub criterion_done: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub advancement_done: Option<bool>,
+}
+
+/// every criterion using this id, with how far the player got
+pub fn usages(data: &Data, kind: SubjectKind, id: &str, uuid: Option<&str>) -> Vec<SubjectUsage> {
+    data.subjects.lookup(kind, id).into_iter()
+        .filter_map(|reference| {
+            let advancement = data.advancements.get(&reference.advancement)?;
+            let progress = uuid.map(|uuid| data.progress.get(&reference.advancement).and_then(|players| players.get(uuid)));
+            Some(SubjectUsage {
+                advancement: reference.advancement.clone(),
+                criterion: reference.criterion.clone(),
+                display_name: advancement.display_name.clone(),
+                criterion_done: progress.map(|p| p.is_some_and(|p| p.has(&reference.criterion))),
+                advancement_done: progress.map(|p| p.is_some_and(|p| p.done)),
+            })
+        })
+        .collect()
+}
+
+/// every term has to show up somewhere, name hits count double
+fn text_score(advancement: &Advancement, terms: &[String]) -> Option<usize> {
+    let name = advancement.display_name.to_lowercase();
+    let description = advancement.description.to_lowercase();
+
+    terms.iter().try_fold(0, |score, term| {
+        if name.contains(term.as_str()) {
+            Some(score + 2)
+        } else if description.contains(term.as_str()) {
+            Some(score + 1)
+        } else {
+            None
+        }
+    })
+}
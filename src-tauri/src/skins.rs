@@ -0,0 +1,245 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+
+// where the head lives on a skin, and the hat layer drawn over it
+const HEAD_FRONT: (u32, u32) = (8, 8);
+const HEAD_TOP: (u32, u32) = (8, 0);
+const HEAD_LEFT: (u32, u32) = (16, 8); // the player's left, on the right of an iso head
+const HAT_OFFSET: u32 = 32;
+
+// iso head shading, like the inventory item
+const TOP_SHADE: f32 = 1.0;
+const FRONT_SHADE: f32 = 0.85;
+const SIDE_SHADE: f32 = 0.7;
+
+// 8x8 faces of the two classic default skins, one char per pixel
+const STEVE_FACE: [&str; 8] = [
+    "HHHHHHHH",
+    "HHHHHHHH",
+    "HssssssH",
+    "ssssssss",
+    "sWBssBWs",
+    "sssNNsss",
+    "ssMMMMss",
+    "ssMssMss",
+];
+
+const ALEX_FACE: [&str; 8] = [
+    "HHHHHHHH",
+    "HHHHHHHH",
+    "HHssssHH",
+    "sssssssH",
+    "sWGssGWs",
+    "ssssssss",
+    "sssMMsss",
+    "ssssssss",
+];
+
+fn steve_color(pixel: char) -> [u8; 4] {
+    match pixel {
+        'H' => [0x2b, 0x1e, 0x0d, 0xff],
+        'W' => [0xff, 0xff, 0xff, 0xff],
+        'B' => [0x52, 0x3d, 0x89, 0xff],
+        'N' => [0x94, 0x60, 0x3f, 0xff],
+        'M' => [0x6a, 0x40, 0x30, 0xff],
+        _ => [0xb6, 0x89, 0x6c, 0xff],
+    }
+}
+
+fn alex_color(pixel: char) -> [u8; 4] {
+    match pixel {
+        'H' => [0xe2, 0x8a, 0x3c, 0xff],
+        'W' => [0xff, 0xff, 0xff, 0xff],
+        'G' => [0x36, 0x8a, 0x3b, 0xff],
+        'M' => [0xd9, 0x7c, 0x6b, 0xff],
+        _ => [0xf5, 0xd0, 0xa9, 0xff],
+    }
+}
+
+/// what the game does for players without a skin, pre 1.19.3: odd java uuid hash is alex
+pub fn is_slim_default(uuid: &str) -> bool {
+    let Ok(uuid) = uuid::Uuid::parse_str(uuid) else { return false };
+    let (most, least) = uuid.as_u64_pair();
+    let hilo = most ^ least;
+    ((hilo >> 32) as i32 ^ hilo as i32) & 1 == 1
+}
+
+/// png of the default skin face a player without a skin would show
+pub fn default_face(uuid: &str) -> Result<Vec<u8>> {
+    let (rows, color): (_, fn(char) -> [u8; 4]) = if is_slim_default(uuid) {
+        (ALEX_FACE, alex_color)
+    } else {
+        (STEVE_FACE, steve_color)
+    };
+
+    let pixels: Vec<u8> = rows.iter().flat_map(|row| row.chars()).flat_map(color).collect();
+    encode_png(8, 8, &pixels)
+}
+
+pub fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(rgba)?;
+    writer.finish()?;
+    Ok(bytes)
+}
+
+/// a decoded skin texture, 64x64 or legacy 64x32, possibly hd
+pub struct Skin {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+impl Skin {
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut decoder = png::Decoder::new(std::io::Cursor::new(bytes));
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        buffer.truncate(info.buffer_size());
+
+        let rgba = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 0xff]).collect(),
+            png::ColorType::GrayscaleAlpha => buffer.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+            png::ColorType::Grayscale => buffer.iter().flat_map(|&v| [v, v, v, 0xff]).collect(),
+            png::ColorType::Indexed => anyhow::bail!("Palette wasn't expanded"),
+        };
+
+        if info.width < 64 || info.width % 64 != 0 || (info.height != info.width && info.height * 2 != info.width) {
+            anyhow::bail!("Not a skin texture: {}x{}", info.width, info.height);
+        }
+        Ok(Skin { width: info.width, height: info.height, rgba })
+    }
+
+    /// pixel in 64x64 skin coordinates, hd skins get sampled nearest
+    fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let scale = self.width / 64;
+        let i = (((y * scale) * self.width + x * scale) * 4) as usize;
+        self.rgba[i..i + 4].try_into().unwrap()
+    }
+
+    /// legacy skins were often saved with a solid hat layer, the game ignores those
+    fn has_hat(&self) -> bool {
+        if self.height == self.width {
+            return true;
+        }
+        (0..8).flat_map(|y| (0..8).map(move |x| (x, y)))
+            .any(|(x, y)| self.pixel(HEAD_FRONT.0 + HAT_OFFSET + x, HEAD_FRONT.1 + y)[3] < 0x80)
+    }
+}
+
+/// the 8x8 face with the hat layer over it, what the tab list shows
+pub fn render_face(skin: &Skin) -> Result<Vec<u8>> {
+    let has_hat = skin.has_hat();
+    let mut pixels = Vec::with_capacity(8 * 8 * 4);
+    for y in 0..8 {
+        for x in 0..8 {
+            let mut color = skin.pixel(HEAD_FRONT.0 + x, HEAD_FRONT.1 + y);
+            color[3] = 0xff;
+            if has_hat {
+                blend(&mut color, skin.pixel(HEAD_FRONT.0 + HAT_OFFSET + x, HEAD_FRONT.1 + y), 1.0);
+            }
+            pixels.extend(color);
+        }
+    }
+    encode_png(8, 8, &pixels)
+}
+
+/// one visible side of the head cube: where texel (0, 0) lands and how far one texel steps along u and v
+struct IsoFace {
+    texture: (u32, u32),
+    origin: (f32, f32),
+    u: (f32, f32),
+    v: (f32, f32),
+    shade: f32,
+}
+
+impl IsoFace {
+    // solve pixel = origin + a * u + b * v
+    fn texel(&self, x: f32, y: f32) -> Option<(u32, u32)> {
+        let (dx, dy) = (x - self.origin.0, y - self.origin.1);
+        let det = self.u.0 * self.v.1 - self.u.1 * self.v.0;
+        let a = (dx * self.v.1 - dy * self.v.0) / det;
+        let b = (self.u.0 * dy - self.u.1 * dx) / det;
+        ((0.0..8.0).contains(&a) && (0.0..8.0).contains(&b)).then_some((a as u32, b as u32))
+    }
+}
+
+/// top, front and left of a cube with `edge` pixel sides, centered in a `size` square
+fn iso_faces(size: f32, edge: f32, texture_offset: u32) -> [IsoFace; 3] {
+    let (cos, sin) = (30f32.to_radians().cos(), 0.5);
+    let right = (cos * edge / 8.0, sin * edge / 8.0);
+    let left = (-cos * edge / 8.0, sin * edge / 8.0);
+    let down = (0.0, edge / 8.0);
+    let up_right = (-left.0, -left.1);
+
+    let top = (size / 2.0, size / 2.0 - edge);
+    let front_corner = (top.0 + left.0 * 8.0, top.1 + left.1 * 8.0);
+    let center = (front_corner.0 + right.0 * 8.0, front_corner.1 + right.1 * 8.0);
+    let texture = |(x, y): (u32, u32)| (x + texture_offset, y);
+
+    [
+        IsoFace { texture: texture(HEAD_TOP), origin: top, u: right, v: left, shade: TOP_SHADE },
+        IsoFace { texture: texture(HEAD_FRONT), origin: front_corner, u: right, v: down, shade: FRONT_SHADE },
+        IsoFace { texture: texture(HEAD_LEFT), origin: center, u: up_right, v: down, shade: SIDE_SHADE },
+    ]
+}
+
+/// isometric head like the inventory item, hat layer sitting half a pixel out
+pub fn render_head(skin: &Skin, size: u32) -> Result<Vec<u8>> {
+    let edge = size as f32 / 2.25; // the hat cube is 9/8 of the head and has to fit
+    let mut layers = vec![iso_faces(size as f32, edge, 0)];
+    if skin.has_hat() {
+        layers.push(iso_faces(size as f32, edge * 9.0 / 8.0, HAT_OFFSET));
+    }
+
+    let mut pixels = vec![0u8; (size * size * 4) as usize];
+    for y in 0..size {
+        for x in 0..size {
+            let pixel: &mut [u8; 4] = (&mut pixels[((y * size + x) * 4) as usize..][..4]).try_into().unwrap();
+            for (depth, faces) in layers.iter().enumerate() {
+                for face in faces {
+                    let Some((u, v)) = face.texel(x as f32 + 0.5, y as f32 + 0.5) else { continue };
+                    let mut color = skin.pixel(face.texture.0 + u, face.texture.1 + v);
+                    if depth == 0 {
+                        color[3] = 0xff; // base layer ignores alpha in game too
+                    }
+                    blend(pixel, color, face.shade);
+                }
+            }
+        }
+    }
+    encode_png(size, size, &pixels)
+}
+
+/// source over destination, with the source darkened by `shade`
+fn blend(destination: &mut [u8; 4], source: [u8; 4], shade: f32) {
+    let alpha = source[3] as f32 / 255.0;
+    for i in 0..3 {
+        let shaded = source[i] as f32 * shade;
+        destination[i] = (shaded * alpha + destination[i] as f32 * (1.0 - alpha)).round() as u8;
+    }
+    destination[3] = (source[3] as f32 + destination[3] as f32 * (1.0 - alpha)).round() as u8;
+}
+
+/// texture id out of a base64 `textures` profile property, the last bit of the skin url
+pub fn skin_texture_id(textures: &str) -> Result<Option<String>> {
+    let decoded = base64::prelude::BASE64_STANDARD.decode(textures)?;
+    let texture_data: serde_json::Value = serde_json::from_slice(&decoded)?;
+
+    let Some(skin_url) = texture_data.get("textures")
+        .and_then(|t| t.get("SKIN"))
+        .and_then(|s| s.get("url"))
+        .and_then(|u| u.as_str()) else {
+        return Ok(None); // default skin
+    };
+
+    let texture_id = skin_url.split("/").last().context("Invalid skin URL format")?.to_string();
+    Ok(Some(texture_id))
+}
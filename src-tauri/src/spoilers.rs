@@ -0,0 +1,93 @@
+use serde::Serialize;
+use std::{borrow::Cow, collections::{HashMap, HashSet}};
+use crate::{load::{Advancement, World}, structs::*};
+
+const REDACTED_NAME: &str = "???";
+const REDACTED_ICON: &str = "barrier"; // same as a missing icon
+
+#[derive(Serialize)]
+struct RedactedData<'a> {
+    world: &'a World,
+    players: &'a HashMap<String, Player>,
+    advancements: HashMap<&'a String, Cow<'a, Advancement>>,
+    categories: &'a HashMap<String, AdvancementCategory>,
+    classes: &'a Vec<String>,
+    progress: HashMap<&'a String, Cow<'a, HashMap<String, AdvancementProgress>>>,
+}
+
+/// like in-game, a hidden advancement hides itself and its children until it's done
+pub fn is_concealed(data: &Data, advancement_id: &str, uuid: &str) -> bool {
+    if data.is_done(advancement_id, uuid) {
+        return false;
+    }
+
+    let mut current_id = Some(advancement_id);
+    let mut visited = HashSet::new();
+    while let Some(id) = current_id {
+        if !visited.insert(id) { break }
+        let Some(advancement) = data.advancements.get(id) else { break };
+        if advancement.hidden && !data.is_done(id, uuid) {
+            return true;
+        }
+        current_id = advancement.parent.as_deref();
+    }
+    false
+}
+
+fn redact(advancement: &Advancement) -> Advancement {
+    let mut redacted = advancement.clone();
+    redacted.display_name = REDACTED_NAME.to_string();
+    redacted.description = String::new();
+    redacted.icon = Icon::Item { name: REDACTED_ICON.to_string(), shimmering: false };
+    redacted.rewards = None;
+    redacted.granted_by.clear(); // execute conditions say where and how
+    redacted.revoked_by.clear();
+    redacted.requirements.clear();
+    redacted.requirement_groups.clear();
+    redacted.stat_goals.clear();
+    redacted.common_subjects = None;
+    redacted.spreadsheet_info.requirement_details = None;
+    redacted
+}
+
+/// the advancement as this player is allowed to see it
+pub fn view<'a>(data: &Data, id: &str, advancement: &'a Advancement, uuid: &str) -> Cow<'a, Advancement> {
+    if is_concealed(data, id, uuid) {
+        Cow::Owned(redact(advancement))
+    } else {
+        Cow::Borrowed(advancement)
+    }
+}
+
+// everyone's criterion names would give a concealed advancement away, completion doesn't
+fn redact_progress(players: &HashMap<String, AdvancementProgress>) -> HashMap<String, AdvancementProgress> {
+    players.iter()
+        .map(|(player, progress)| (player.clone(), AdvancementProgress { requirement_progress: HashMap::new(), ..progress.clone() }))
+        .collect()
+}
+
+pub fn redacted_response_bytes(data: &Data, uuid: &str) -> bytes::Bytes {
+    let advancements = data.advancements.iter()
+        .map(|(id, advancement)| (id, view(data, id, advancement, uuid)))
+        .collect();
+
+    let progress = data.progress.iter()
+        .map(|(id, players)| {
+            if is_concealed(data, id, uuid) {
+                (id, Cow::Owned(redact_progress(players)))
+            } else {
+                (id, Cow::Borrowed(players))
+            }
+        })
+        .collect();
+
+    let redacted = RedactedData {
+        world: &data.world,
+        players: &data.players,
+        advancements,
+        categories: &data.categories,
+        classes: &data.classes,
+        progress,
+    };
+    bytes::Bytes::from(serde_json::to_vec(&redacted).unwrap())
+}
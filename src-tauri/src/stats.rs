@@ -0,0 +1,148 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use crate::{load::{Advancement, BaseSubject, ObjectiveGoal}, structs::*};
+
+// time stats count ticks
+const TICK_STATS: [&str; 6] = ["play_time", "play_one_minute", "total_world_time", "time_since_death", "time_since_rest", "sneak_time"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatProgress {
+    pub advancement: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub criterion: Option<String>,
+    pub stat_type: String,
+    pub target: String,
+    pub current: i64,
+    pub goal: i64,
+    pub done: bool,
+    pub display: DisplayValue,
+}
+
+/// current and goal in a unit people read, like km instead of cm
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayValue {
+    pub current: f64,
+    pub goal: f64,
+    pub unit: &'static str,
+}
+
+impl DisplayValue {
+    fn new(target: &str, current: i64, goal: i64) -> Self {
+        let (divisor, unit) = display_unit(target, goal);
+        DisplayValue {
+            current: current as f64 / divisor,
+            goal: goal as f64 / divisor,
+            unit,
+        }
+    }
+}
+
+// the unit is picked from the goal so both numbers read the same way
+fn display_unit(target: &str, goal: i64) -> (f64, &'static str) {
+    if target.ends_with("_one_cm") {
+        match goal {
+            g if g >= 100_000 => (100_000.0, "km"),
+            g if g >= 100 => (100.0, "m"),
+            _ => (1.0, "cm"),
+        }
+    } else if TICK_STATS.contains(&target) {
+        match goal {
+            g if g >= 72_000 => (72_000.0, "h"),
+            g if g >= 1_200 => (1_200.0, "min"),
+            _ => (20.0, "s"),
+        }
+    } else if target.starts_with("damage_") {
+        (20.0, "hearts") // tenths of a health point
+    } else {
+        (1.0, "")
+    }
+}
+
+/// every stat based criterion and stat goal, measured against the player's stats file
+pub fn stat_progress(data: &Data, uuid: &str) -> Vec<StatProgress> {
+    let Some(player) = data.players.get(uuid) else {
+        return Vec::new();
+    };
+
+    let mut keys: Vec<&String> = data.advancements.keys().collect();
+    keys.sort();
+
+    let mut entries = Vec::new();
+    for key in keys {
+        let advancement = &data.advancements[key];
+        let progress = data.progress.get(key).and_then(|players| players.get(uuid));
+        for (criterion, stat_type, target, goal) in stat_targets(advancement) {
+            let current = player.stats.stats.get(stat_type).and_then(|stats| stats.get(target)).copied().unwrap_or(0);
+            let already_done = progress.is_some_and(|progress| match criterion {
+                Some(criterion) => progress.has(criterion),
+                None => progress.done,
+            });
+
+            entries.push(StatProgress {
+                advancement: key.clone(),
+                criterion: criterion.cloned(),
+                stat_type: stat_type.to_string(),
+                target: target.to_string(),
+                current, goal,
+                done: already_done || current >= goal,
+                display: DisplayValue::new(target, current, goal),
+            });
+        }
+    }
+    entries
+}
+
+/// only the entries whose stat moved
+pub fn changed_stat_progress(data: &Data, uuid: &str, deltas: &HashMap<String, HashMap<String, i64>>) -> Vec<StatProgress> {
+    stat_progress(data, uuid).into_iter()
+        .filter(|entry| deltas.get(&entry.stat_type).is_some_and(|stats| stats.contains_key(&entry.target)))
+        .collect()
+}
+
+fn stat_targets(advancement: &Advancement) -> Vec<(Option<&String>, &str, &str, i64)> {
+    let mut targets = Vec::new();
+    for (criterion, subjects) in &advancement.requirements {
+        for subject in subjects {
+            if let BaseSubject::Stat { stat_type, target, value } = &subject.base {
+                targets.push((Some(criterion), stat_type.as_str(), target.as_str(), *value as i64));
+            }
+        }
+    }
+    for goal in &advancement.stat_goals {
+        targets.push((goal.criterion.as_ref(), goal.stat_type.as_str(), goal.target.as_str(), goal.value));
+    }
+    targets
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectiveProgress {
+    pub objective: String,
+    pub advancement: String,
+    pub current: i32,
+    pub target: i32,
+    pub done: bool,
+}
+
+/// configured objective goals, scores are kept per player name (or uuid for entities)
+pub fn objective_progress(data: &Data, goals: &[ObjectiveGoal], uuid: &str) -> Vec<ObjectiveProgress> {
+    let name = data.players.get(uuid).and_then(|player| player.name.as_deref());
+    let scores = name.and_then(|name| data.scoreboard.scores.get(name))
+        .or_else(|| data.scoreboard.scores.get(uuid));
+
+    goals.iter()
+        .map(|goal| {
+            let current = scores.and_then(|scores| scores.get(&goal.objective)).copied().unwrap_or(0);
+            let advancement_done = data.is_done(&goal.advancement, uuid);
+            ObjectiveProgress {
+                objective: goal.objective.clone(),
+                advancement: goal.advancement.clone(),
+                current,
+                target: goal.target,
+                done: advancement_done || current >= goal.target,
+            }
+        })
+        .collect()
+}
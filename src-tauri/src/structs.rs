@@ -1,6 +1,7 @@
 
 use std::{collections::HashMap};
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Deserializer, Serialize};
 use crate::load::Advancement;
 
 #[derive(Debug, Serialize, Default)]
@@ -13,9 +14,32 @@ pub struct Data {
 
     // below this is subject to change after startup
     pub progress: HashMap<String, HashMap<String, AdvancementProgress>>, // advancement, playerid, player progress
+
+    #[serde(skip)]
+    pub worldgen: crate::load::Worldgen,
+    #[serde(skip)]
+    pub subjects: crate::load::SubjectIndex,
+    #[serde(skip)]
+    pub scoreboard: crate::load::Scoreboard, // served on its own
+    #[serde(skip)]
+    pub locations: crate::load::LocationIndex, // filled in the background, region scans are slow
 }
 
 
+impl Data {
+    /// one player's progress, keyed by advancement
+    pub fn player_progress(&self, uuid: &str) -> HashMap<String, AdvancementProgress> {
+        self.progress.iter()
+            .filter_map(|(advancement, players)| players.get(uuid).map(|progress| (advancement.clone(), progress.clone())))
+            .collect()
+    }
+
+    pub fn is_done(&self, advancement_id: &str, uuid: &str) -> bool {
+        self.progress.get(advancement_id)
+            .and_then(|players| players.get(uuid))
+            .is_some_and(|progress| progress.done)
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
@@ -24,6 +48,10 @@ pub struct Player {
     pub stats: PlayerStats,
     pub name: Option<String>, // online
     pub avatar_url: Option<String>, // online
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<String>, // only for teams
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<crate::load::PlayerState>, // position, inventory... from playerdata
 }
 
 
@@ -44,11 +72,11 @@ pub enum Icon {
     PlayerHead { texture_id: String },
 }
 
-fn is_false(b: &bool) -> bool {
+pub fn is_false(b: &bool) -> bool {
     !*b
 }
 
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum AdvancementType {
     Root,
@@ -60,9 +88,42 @@ pub enum AdvancementType {
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct AdvancementProgress {
-    #[serde(alias = "criteria")]
-    pub requirement_progress: HashMap<String, String>,
+    #[serde(alias = "criteria", deserialize_with = "deserialize_criteria_dates")]
+    pub requirement_progress: HashMap<String, Timestamp>,
     pub done: bool,
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<Timestamp>, // when the last needed criterion came in
+}
+
+pub type Timestamp = DateTime<FixedOffset>;
+
+const MC_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S %z";
+
+// "2025-06-19 23:36:15 +0200"
+pub fn parse_mc_date(date: &str) -> Option<Timestamp> {
+    DateTime::parse_from_str(date, MC_DATE_FORMAT)
+        .or_else(|_| DateTime::parse_from_rfc3339(date))
+        .ok()
+}
+
+/// a criterion with a date we can't read is skipped, not the whole advancement
+fn deserialize_criteria_dates<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<String, Timestamp>, D::Error> {
+    let raw = HashMap::<String, String>::deserialize(deserializer)?;
+    Ok(raw.into_iter()
+        .filter_map(|(criterion, date)| match parse_mc_date(&date) {
+            Some(timestamp) => Some((criterion, timestamp)),
+            None => {
+                println!("[WARN] Skipping criterion {} with invalid date '{}'", criterion, date);
+                None
+            }
+        })
+        .collect())
+}
+
+impl AdvancementProgress {
+    pub fn has(&self, criterion: &str) -> bool {
+        self.requirement_progress.contains_key(criterion)
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -71,6 +132,9 @@ pub struct AdvancementCategory {
     pub key: String,
     pub display_name: String,
     pub icon: Icon,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    pub layout: crate::load::TreeLayout,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -79,4 +143,6 @@ pub struct SpreadsheetInfo {
     pub class: String,
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub requirement_details: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub time: Option<String>, // rough estimate, free text
 }
\ No newline at end of file
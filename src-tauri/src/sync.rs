@@ -0,0 +1,161 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::{borrow::Cow, collections::{BTreeMap, HashMap}};
+use crate::{load::{Advancement, World}, structs::*};
+
+/// serialized json with its etag
+#[derive(Clone)]
+pub struct Payload {
+    pub etag: String,
+    pub bytes: bytes::Bytes,
+}
+
+impl Payload {
+    pub fn hashed(bytes: Vec<u8>) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(&bytes);
+        Payload { etag: hex::encode(hasher.finalize()), bytes: bytes::Bytes::from(bytes) }
+    }
+}
+
+// the parts of Data that don't change after startup
+#[derive(Serialize)]
+struct Definitions<'a, A: Serialize> {
+    world: &'a World,
+    advancements: A,
+    categories: &'a HashMap<String, AdvancementCategory>,
+    classes: &'a Vec<String>,
+}
+
+fn definitions_bytes<A: Serialize>(data: &Data, advancements: A) -> Vec<u8> {
+    let definitions = Definitions {
+        world: &data.world,
+        advancements,
+        categories: &data.categories,
+        classes: &data.classes,
+    };
+    serde_json::to_vec(&definitions).unwrap()
+}
+
+pub fn definitions_payload(data: &Data) -> Payload {
+    Payload::hashed(definitions_bytes(data, &data.advancements))
+}
+
+/// definitions as one player may see them in spoiler mode
+pub fn redacted_definitions_payload(data: &Data, uuid: &str) -> Payload {
+    let advancements: HashMap<&String, Cow<Advancement>> = data.advancements.iter()
+        .map(|(id, advancement)| (id, crate::spoilers::view(data, id, advancement, uuid)))
+        .collect();
+    Payload::hashed(definitions_bytes(data, &advancements))
+}
+
+#[derive(Serialize)]
+struct PlayerSlice<'a> {
+    player: Option<&'a Player>,
+    progress: HashMap<String, AdvancementProgress>,
+}
+
+struct Slice {
+    revision: u64,
+    bytes: bytes::Bytes,
+}
+
+/// per-player serialized progress, each stamped with the revision it last changed at
+pub struct ProgressSync {
+    revision: u64,
+    slices: BTreeMap<String, Slice>,
+    removed: BTreeMap<String, u64>, // tombstones, so deltas can tell clients to drop a player
+}
+
+impl ProgressSync {
+    pub fn new(data: &Data) -> Self {
+        let mut sync = ProgressSync { revision: 0, slices: BTreeMap::new(), removed: BTreeMap::new() };
+        for uuid in data.players.keys() {
+            sync.update_player(data, uuid);
+        }
+        sync
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// re-serialize only this player's slice
+    pub fn update_player(&mut self, data: &Data, uuid: &str) {
+        let slice = PlayerSlice {
+            player: data.players.get(uuid),
+            progress: data.player_progress(uuid),
+        };
+        self.revision += 1;
+        self.removed.remove(uuid);
+        self.slices.insert(uuid.to_string(), Slice {
+            revision: self.revision,
+            bytes: bytes::Bytes::from(serde_json::to_vec(&slice).unwrap()),
+        });
+    }
+
+    pub fn remove_player(&mut self, uuid: &str) {
+        self.revision += 1;
+        if self.slices.remove(uuid).is_some() {
+            self.removed.insert(uuid.to_string(), self.revision);
+        }
+    }
+
+    /// {"revision": n, "players": {uuid: {player, progress}}, "removed": [uuid]} with what changed after `since`
+    pub fn delta(&self, since: Option<u64>) -> bytes::Bytes {
+        // a full snapshot has nothing to remove
+        let removed: Vec<&String> = match since {
+            Some(since) => self.removed.iter().filter(|(_, revision)| **revision > since).map(|(uuid, _)| uuid).collect(),
+            None => Vec::new(),
+        };
+
+        let since = since.unwrap_or(0);
+        let mut out = format!("{{\"revision\":{},\"players\":{{", self.revision).into_bytes();
+
+        let changed = self.slices.iter().filter(|(_, slice)| slice.revision > since);
+        for (i, (uuid, slice)) in changed.enumerate() {
+            if i > 0 {
+                out.push(b',');
+            }
+            out.extend(serde_json::to_vec(uuid).unwrap());
+            out.push(b':');
+            out.extend_from_slice(&slice.bytes);
+        }
+
+        out.extend_from_slice(b"},\"removed\":");
+        out.extend(serde_json::to_vec(&removed).unwrap());
+        out.push(b'}');
+        bytes::Bytes::from(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delta(sync: &ProgressSync, since: Option<u64>) -> serde_json::Value {
+        serde_json::from_slice(&sync.delta(since)).unwrap()
+    }
+
+    #[test]
+    fn removed_player_shows_up_in_the_next_delta() {
+        let mut data = Data::default();
+        for uuid in ["alive", "gone"] {
+            data.players.insert(uuid.to_string(), Player { uuid: uuid.to_string(), ..Default::default() });
+        }
+        let mut sync = ProgressSync::new(&data);
+        let before = sync.revision();
+
+        data.players.remove("gone");
+        sync.remove_player("gone");
+
+        let changes = delta(&sync, Some(before));
+        assert_eq!(changes["removed"], serde_json::json!(["gone"]));
+        assert!(changes["players"].as_object().unwrap().is_empty());
+
+        // caught up clients and fresh snapshots don't get it again
+        assert_eq!(delta(&sync, Some(sync.revision()))["removed"], serde_json::json!([]));
+        assert_eq!(delta(&sync, None)["removed"], serde_json::json!([]));
+        assert!(delta(&sync, None)["players"].get("alive").is_some());
+    }
+}
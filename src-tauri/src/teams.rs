@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::{events::UpdateEvent, load::{completion_time, Advancement}, structs::*, AppState};
+
+// teams live next to players in Data under this prefix
+pub const TEAM_PREFIX: &str = "team:";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Team {
+    pub name: String,
+    pub members: Vec<String>, // uuids
+}
+
+impl Team {
+    pub fn id(&self) -> String {
+        format!("{}{}", TEAM_PREFIX, self.name)
+    }
+}
+
+pub fn is_team(uuid: &str) -> bool {
+    uuid.starts_with(TEAM_PREFIX)
+}
+
+/// a criterion counts if any member has it (earliest time wins), then the requirements decide
+pub fn merged_progress(data: &Data, members: &[String]) -> HashMap<String, AdvancementProgress> {
+    let mut merged: HashMap<String, AdvancementProgress> = HashMap::new();
+    for (key, players) in &data.progress {
+        for member in members {
+            let Some(progress) = players.get(member) else { continue };
+            let entry = merged.entry(key.clone()).or_default();
+            for (criterion, at) in &progress.requirement_progress {
+                entry.requirement_progress.entry(criterion.clone())
+                    .and_modify(|earliest| if at < earliest { *earliest = *at })
+                    .or_insert(*at);
+            }
+            entry.done |= progress.done; // command driven ones can't be evaluated
+        }
+    }
+
+    for (key, progress) in merged.iter_mut() {
+        let advancement = data.advancements.get(key);
+        progress.done |= advancement.is_some_and(|advancement| meets_requirements(advancement, progress));
+        progress.completed_at = completion_time(advancement, progress);
+    }
+    merged
+}
+
+fn meets_requirements(advancement: &Advancement, progress: &AdvancementProgress) -> bool {
+    !advancement.command_driven
+        && !advancement.requirement_groups.is_empty()
+        && advancement.unmet_groups(Some(progress)).is_empty()
+}
+
+fn merged_stats(data: &Data, members: &[String]) -> PlayerStats {
+    let mut stats = PlayerStats::default();
+    for player in members.iter().filter_map(|member| data.players.get(member)) {
+        for (category, values) in &player.stats.stats {
+            let merged_category = stats.stats.entry(category.clone()).or_default();
+            for (stat, value) in values {
+                *merged_category.entry(stat.clone()).or_insert(0) += value;
+            }
+        }
+    }
+    stats
+}
+
+/// put the team into Data like any other player, returns the progress it had before
+pub fn apply(data: &mut Data, team: &Team) -> HashMap<String, AdvancementProgress> {
+    let id = team.id();
+    let previous = data.player_progress(&id);
+    let progress = merged_progress(data, &team.members);
+    let stats = merged_stats(data, &team.members);
+
+    remove(data, &id);
+    for (key, team_progress) in progress {
+        data.progress.entry(key).or_default().insert(id.clone(), team_progress);
+    }
+    data.players.insert(id.clone(), Player {
+        uuid: id,
+        stats,
+        name: Some(team.name.clone()),
+        avatar_url: None,
+        members: team.members.clone(),
+        ..Default::default()
+    });
+    previous
+}
+
+pub fn remove(data: &mut Data, id: &str) {
+    data.players.remove(id);
+    for players in data.progress.values_mut() {
+        players.remove(id);
+    }
+}
+
+/// recompute a team and push the changes out the same way a player file update does
+pub fn refresh(app: &mut AppState, team: &Team) {
+    let id = team.id();
+    let previous = apply(&mut app.data, team);
+    let progress = app.data.player_progress(&id);
+
+    let changes = crate::history::diff_progress(&id, &previous, &progress, chrono::Local::now().fixed_offset());
+    for event in crate::events::progress_events(&app.data, &changes) {
+        app.events.publish(event);
+    }
+
+    app.events.publish(UpdateEvent::ProgressUpdate {
+        uuid: id.clone(),
+        player: app.data.players[&id].clone(),
+        updated_progress: progress,
+    });
+    app.progress_sync.update_player(&app.data, &id);
+}
+
+/// teams that need recomputing after this player changed
+pub fn teams_with(app: &AppState, uuid: &str) -> Vec<Team> {
+    app.config.teams.iter()
+        .filter(|team| team.members.iter().any(|member| member == uuid))
+        .cloned()
+        .collect()
+}
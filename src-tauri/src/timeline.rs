@@ -0,0 +1,30 @@
+use serde::Serialize;
+use crate::structs::*;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEntry {
+    pub advancement: String,
+    pub display_name: String,
+    pub advancement_type: AdvancementType,
+    pub completed_at: Timestamp,
+}
+
+/// completed advancements for a player, oldest first
+pub fn timeline(data: &Data, uuid: &str) -> Vec<TimelineEntry> {
+    let mut entries: Vec<TimelineEntry> = data.progress.iter()
+        .filter_map(|(key, players)| {
+            let completed_at = players.get(uuid)?.completed_at?;
+            let advancement = data.advancements.get(key)?;
+            Some(TimelineEntry {
+                advancement: key.clone(),
+                display_name: advancement.display_name.clone(),
+                advancement_type: advancement.advancement_type.clone(),
+                completed_at,
+            })
+        })
+        .collect();
+
+    entries.sort_by(|a, b| a.completed_at.cmp(&b.completed_at).then_with(|| a.advancement.cmp(&b.advancement)));
+    entries
+}
@@ -31,6 +31,11 @@ eventSource.onmessage = (event) => {
     });
 };
 
+// the server couldn't replay what we missed, start over from /api/init
+eventSource.addEventListener("resync", () => {
+    window.location.reload();
+});
+
 eventSource.onerror = (err) => {
     console.error("EventSource connection failed:", err);
 };
\ No newline at end of file
@@ -11,7 +11,7 @@ export function getIconUrl(icon) {
 
     switch (icon.type) {
         case "playerHead":
-            return icon.textureId ? `/heads/${icon.textureId}.png` : "/icons/minecraft_player_head.png";
+            return icon.textureId ? `/cached/heads/${icon.textureId}.png` : "/icons/minecraft_player_head.png";
 
         case "item":
             return icon.name ? `/icons/minecraft_${icon.name}.png` : "/icons/minecraft_stone.png";
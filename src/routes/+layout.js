@@ -5,7 +5,10 @@ export async function load({ fetch }) {
     console.log("Fetching bootstrap data in load function...");
     
     try {
-        const response = await fetch(`/api/init`);
+        // spoiler mode on the server redacts hidden advancements for this player
+        const settings = JSON.parse(localStorage.getItem("client-settings") || "{}");
+        const query = settings.selectedPlayerUuid ? `?player=${settings.selectedPlayerUuid}` : "";
+        const response = await fetch(`/api/init${query}`);
 
         console.log(response);
 